@@ -1,75 +1,80 @@
 advent_of_code::solution!(1);
 
-use regex::Regex;
-
-pub fn part_one(input: &str) -> Option<u32> {
-    let lines = input.split('\n');
-    let non_empty_lines = lines.filter(|line| !line.is_empty());
-    let digits = non_empty_lines.map(digits);
-    let values = digits.map(|(first, last)| first * 10u32 + last);
-    values.reduce(|acc, e| acc + e)
+/// The tokens that stand for a digit, e.g. `"7"` and `"seven"` for 7.
+pub struct Vocabulary {
+    /// Sorted by decreasing length, so that e.g. "seventeen" is preferred over "seven".
+    tokens: Vec<(String, u32)>,
 }
 
-pub fn digits(input: &str) -> (u32, u32) {
-    let first = input
-        .chars()
-        .filter(char::is_ascii_digit)
-        .map(|c| c.to_digit(10).unwrap())
-        .next();
-    let last = input
-        .chars()
-        .rev()
-        .filter(char::is_ascii_digit)
-        .map(|c| c.to_digit(10).unwrap())
-        .next();
-    (first.unwrap(), last.unwrap())
+impl Vocabulary {
+    /// Only the digits `0` to `9`.
+    pub fn digits() -> Self {
+        Self::from_words(&[])
+    }
+
+    /// The digits and the english words from "zero" to "nine".
+    pub fn english() -> Self {
+        Self::from_words(&[
+            "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+        ])
+    }
+
+    /// The digits and words for the values 0, 1, 2, ... in this order, e.g. of another
+    /// language or continuing up to "twenty".
+    pub fn from_words(words: &[&str]) -> Self {
+        let digits = (0..10).map(|d| (d.to_string(), d));
+        let words = words
+            .iter()
+            .zip(0..)
+            .map(|(w, value)| (w.to_string(), value));
+
+        let mut tokens: Vec<(String, u32)> = digits.chain(words).collect();
+        tokens.sort_by_key(|(token, _)| std::cmp::Reverse(token.len()));
+        Self { tokens }
+    }
+
+    /// The value of the token that starts at `position` of `line`.
+    fn token_at(&self, line: &str, position: usize) -> Option<u32> {
+        let rest = &line[position..];
+        self.tokens
+            .iter()
+            .find(|(token, _)| rest.starts_with(token.as_str()))
+            .map(|(_, value)| *value)
+    }
+
+    /// The values of the first and the last token of `line`. Tokens may overlap, e.g. "twone"
+    /// contains a two followed by a one, so a token is looked up at every position: from the
+    /// start for the first one and from the end for the last one, checking each position once.
+    pub fn first_and_last(&self, line: &str) -> Option<(u32, u32)> {
+        let mut values = line
+            .char_indices()
+            .filter_map(|(position, _)| self.token_at(line, position));
+        let first = values.next()?;
+        let last = values.next_back().unwrap_or(first);
+        Some((first, last))
+    }
 }
 
-pub fn part_two(_input: &str) -> Option<u32> {
-    let first_digit_regex =
-        Regex::new(r"(\d|one|two|three|four|five|six|seven|eight|nine|zero).*?").unwrap();
-    let last_digit_regex =
-        Regex::new(r".*(\d|one|two|three|four|five|six|seven|eight|nine|zero)").unwrap();
-    let lines = _input.split('\n');
-    let non_empty_lines = lines.filter(|line| !line.is_empty());
-    let digits = non_empty_lines.map(|line| {
-        (
-            first_digit_regex
-                .captures(line)
-                .unwrap()
-                .get(1)
-                .unwrap()
-                .as_str(),
-            last_digit_regex
-                .captures(line)
-                .unwrap()
-                .get(1)
-                .unwrap()
-                .as_str(),
-        )
-    });
-    let values = digits
-        .map(|(first, last)| parse_digit_from_str(first) * 10u32 + parse_digit_from_str(last));
-    values.reduce(|acc, e| acc + e)
+fn calibration_sum(input: &str, vocabulary: &Vocabulary) -> Result<u32, String> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(idx, line)| {
+            let (first, last) = vocabulary
+                .first_and_last(line)
+                .ok_or_else(|| format!("line {} contains no digit: `{line}`", idx + 1))?;
+            Ok(first * 10 + last)
+        })
+        .sum()
 }
 
-pub fn parse_digit_from_str(_input: &str) -> u32 {
-    match _input.len() {
-        1 => _input.chars().next().unwrap().to_digit(10).unwrap(),
-        _ => match _input {
-            "one" => 1u32,
-            "two" => 2u32,
-            "three" => 3u32,
-            "four" => 4u32,
-            "five" => 5u32,
-            "six" => 6u32,
-            "seven" => 7u32,
-            "eight" => 8u32,
-            "nine" => 9u32,
-            "zero" => 0u32,
-            _ => panic!("Not implemented {_input}"),
-        },
-    }
+pub fn part_one(input: &str) -> Result<u32, String> {
+    calibration_sum(input, &Vocabulary::digits())
+}
+
+pub fn part_two(input: &str) -> Result<u32, String> {
+    calibration_sum(input, &Vocabulary::english())
 }
 
 #[cfg(test)]
@@ -79,12 +84,75 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(55u32));
+        assert_eq!(result, Ok(55u32));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(52u32));
+        assert_eq!(result, Ok(52u32));
+    }
+
+    #[test]
+    fn test_overlapping_words() {
+        let english = Vocabulary::english();
+        assert_eq!(english.first_and_last("twone"), Some((2, 1)));
+        assert_eq!(english.first_and_last("eightwo"), Some((8, 2)));
+        assert_eq!(english.first_and_last("xtwonex"), Some((2, 1)));
+        assert_eq!(english.first_and_last("oneight"), Some((1, 8)));
+        assert_eq!(english.first_and_last("seven"), Some((7, 7)));
+        assert_eq!(english.first_and_last("4nineeightseven2"), Some((4, 2)));
+        assert_eq!(Vocabulary::digits().first_and_last("twone3"), Some((3, 3)));
+    }
+
+    #[test]
+    fn test_other_vocabularies() {
+        let german = Vocabulary::from_words(&[
+            "null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+        ]);
+        assert_eq!(german.first_and_last("fünfzehnachteins"), Some((5, 1)));
+        assert_eq!(german.first_and_last("xsiebenullx"), Some((7, 0)));
+
+        let up_to_twenty = Vocabulary::from_words(&[
+            "zero",
+            "one",
+            "two",
+            "three",
+            "four",
+            "five",
+            "six",
+            "seven",
+            "eight",
+            "nine",
+            "ten",
+            "eleven",
+            "twelve",
+            "thirteen",
+            "fourteen",
+            "fifteen",
+            "sixteen",
+            "seventeen",
+            "eighteen",
+            "nineteen",
+            "twenty",
+        ]);
+        assert_eq!(
+            up_to_twenty.first_and_last("seventeentwelve"),
+            Some((17, 12))
+        );
+        assert_eq!(up_to_twenty.first_and_last("twentwenty"), Some((20, 20)));
+        assert_eq!(
+            calibration_sum("nineteen5\n", &up_to_twenty),
+            Ok(19 * 10 + 5)
+        );
+    }
+
+    #[test]
+    fn test_reports_malformed_lines() {
+        assert_eq!(
+            part_two("two1nine\nabc\n"),
+            Err("line 2 contains no digit: `abc`".to_string())
+        );
+        assert_eq!(part_one("1abc2\r\n"), Ok(12));
     }
 }
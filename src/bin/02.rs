@@ -1,91 +1,135 @@
-advent_of_code::solution!(2);
+advent_of_code::solution!(
+    2,
+    params {
+        bag: Cubes = "12 red, 13 green, 14 blue".parse().unwrap()
+    }
+);
+
+use advent_of_code::parse::{lines, scan};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// The colour of a cube, any name that occurs in the input.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Colour(String);
+
+impl Display for Colour {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Numbers of cubes by colour, either shown in a round of a game or contained in a bag.
+/// Colours that are missing have no cubes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cubes(HashMap<Colour, u32>);
+
+impl Cubes {
+    pub fn count(&self, colour: &Colour) -> u32 {
+        self.0.get(colour).copied().unwrap_or(0)
+    }
+
+    pub fn colours(&self) -> impl Iterator<Item = &Colour> {
+        self.0.keys()
+    }
 
-use regex::Regex;
+    /// Whether there are at least as many cubes of every colour as in `other`.
+    pub fn contains(&self, other: &Cubes) -> bool {
+        other
+            .0
+            .iter()
+            .all(|(colour, count)| *count <= self.count(colour))
+    }
 
-fn validate(color: &str, number: u32) -> bool {
-    match color {
-        "red" => number <= 12,
-        "green" => number <= 13,
-        "blue" => number <= 14,
-        _ => false,
+    /// Raises the number of cubes of every colour to at least the number shown in `round`.
+    fn fit(&mut self, round: &HashMap<Colour, u32>) {
+        for (colour, count) in round {
+            let current = self.0.entry(colour.clone()).or_insert(0);
+            *current = (*current).max(*count);
+        }
+    }
+
+    /// The product of the numbers of cubes of the given colours.
+    pub fn power<'a>(&self, colours: impl IntoIterator<Item = &'a Colour>) -> u32 {
+        colours
+            .into_iter()
+            .map(|colour| self.count(colour))
+            .product()
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let line_regex = Regex::new(r"Game (?<id>\d+): (?<game>.*)").unwrap();
-    let cube_regex = Regex::new(r"(?<number>\d+) (?<color>[[:alpha:]]+)").unwrap();
-    let lines = input.split('\n');
-    let non_empty_lines = lines.filter(|line| !line.is_empty());
-    let ids_and_games = non_empty_lines.map(|line| {
-        let caps = line_regex.captures(line).unwrap();
-        (
-            caps.name("id").unwrap().as_str(),
-            caps.name("game").unwrap().as_str(),
-        )
-    });
-    let possible_ids_and_games = ids_and_games.filter(|(_id, game)| {
-        cube_regex.captures_iter(game).all(|cap| {
-            validate(
-                cap.name("color").unwrap().as_str(),
-                cap.name("number").unwrap().as_str().parse::<u32>().unwrap(),
-            )
-        })
-    });
-    let possible_ids_parsed = possible_ids_and_games.map(|(id, _game)| id.parse::<u32>().unwrap());
-    possible_ids_parsed.reduce(|acc, e| acc + e)
-    // Game 1: 1 red, 3 blue, 11 green; 1 blue, 5 red; 3 blue, 5 green, 13 red; 6 red, 1 blue, 4 green; 16 red, 12 green
+/// Parses a list like `3 blue, 4 red`. A colour that is listed twice counts twice.
+impl FromStr for Cubes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut cubes = HashMap::new();
+        for entry in s.split(',') {
+            let (count, colour): (u32, &str) =
+                scan("{} {}", entry.trim()).map_err(|e| format!("`{entry}` {e}"))?;
+            *cubes.entry(Colour(colour.to_string())).or_insert(0) += count;
+        }
+        Ok(Cubes(cubes))
+    }
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let line_regex = Regex::new(r"Game (?<id>\d+): (?<game>.*)").unwrap();
-    let red_regex = Regex::new(r"(?<number>\d+) red").unwrap();
-    let blue_regex = Regex::new(r"(?<number>\d+) blue").unwrap();
-    let green_regex = Regex::new(r"(?<number>\d+) green").unwrap();
-    let lines = input.split('\n');
-    let non_empty_lines = lines.filter(|line| !line.is_empty());
-    let ids_and_games = non_empty_lines.map(|line| {
-        let caps = line_regex.captures(line).unwrap();
-        (
-            caps.name("id").unwrap().as_str(),
-            caps.name("game").unwrap().as_str(),
-        )
-    });
-    let game_powers = ids_and_games.map(|(_id, game)| {
-        red_regex
-            .captures_iter(game)
-            .map(|caps| {
-                caps.name("number")
-                    .unwrap()
-                    .as_str()
-                    .parse::<u32>()
-                    .unwrap()
-            })
-            .max()
-            .unwrap_or(0)
-            * blue_regex
-                .captures_iter(game)
-                .map(|caps| {
-                    caps.name("number")
-                        .unwrap()
-                        .as_str()
-                        .parse::<u32>()
-                        .unwrap()
-                })
-                .max()
-                .unwrap_or(0)
-            * green_regex
-                .captures_iter(game)
-                .map(|caps| {
-                    caps.name("number")
-                        .unwrap()
-                        .as_str()
-                        .parse::<u32>()
-                        .unwrap()
-                })
-                .max()
-                .unwrap_or(0)
-    });
-    game_powers.reduce(|acc, e| acc + e)
+/* -------------------------------------------------------------------------- */
+
+pub struct Game {
+    pub id: u32,
+    pub rounds: Vec<HashMap<Colour, u32>>,
+}
+
+impl Game {
+    fn parse(line: &str) -> Result<Self, String> {
+        let (id, rounds): (u32, &str) = scan("Game {}: {}", line).map_err(|e| e.to_string())?;
+        let rounds = rounds
+            .split(';')
+            .map(|round| round.parse::<Cubes>().map(|cubes| cubes.0))
+            .collect::<Result<_, _>>()?;
+        Ok(Game { id, rounds })
+    }
+
+    /// The smallest bag that the cubes of every round could have been drawn from.
+    pub fn min_bag(&self) -> Cubes {
+        let mut bag = Cubes::default();
+        for round in &self.rounds {
+            bag.fit(round);
+        }
+        bag
+    }
+
+    pub fn is_possible_with(&self, bag: &Cubes) -> bool {
+        bag.contains(&self.min_bag())
+    }
+}
+
+fn parse_games(input: &str) -> Result<Vec<Game>, String> {
+    lines(input)
+        .enumerate()
+        .map(|(idx, line)| Game::parse(line).map_err(|e| format!("line {}: {e}", idx + 1)))
+        .collect()
+}
+
+pub fn part_one(input: &str, params: &Params) -> Result<u32, String> {
+    let games = parse_games(input)?;
+    Ok(games
+        .iter()
+        .filter(|game| game.is_possible_with(&params.bag))
+        .map(|game| game.id)
+        .sum())
+}
+
+pub fn part_two(input: &str) -> Result<u32, String> {
+    let games = parse_games(input)?;
+    let min_bags: Vec<Cubes> = games.iter().map(Game::min_bag).collect();
+    // a colour that some game never shows makes the power of that game zero.
+    let colours: BTreeSet<&Colour> = min_bags.iter().flat_map(Cubes::colours).collect();
+    Ok(min_bags
+        .iter()
+        .map(|bag| bag.power(colours.iter().copied()))
+        .sum())
 }
 
 #[cfg(test)]
@@ -94,13 +138,54 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(8u32));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_one(&input, &Params::default());
+        assert_eq!(result, Ok(8u32));
+        let result = part_one(
+            &input,
+            &Params {
+                bag: "20 red, 20 green, 20 blue".parse().unwrap(),
+            },
+        );
+        assert_eq!(result, Ok(15u32));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(2286u32));
+        assert_eq!(result, Ok(2286u32));
+    }
+
+    #[test]
+    fn test_min_bag() {
+        let games = parse_games(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(games.len(), 5);
+        assert_eq!(games[0].rounds.len(), 3);
+        assert_eq!(
+            games[0].min_bag(),
+            "4 red, 2 green, 6 blue".parse().unwrap()
+        );
+        assert!(games[0].is_possible_with(&games[0].min_bag()));
+        assert!(!games[2].is_possible_with(&Params::default().bag));
+    }
+
+    #[test]
+    fn test_other_colours() {
+        let input = "Game 1: 2 yellow, 1 red; 1 yellow\nGame 7: 3 red\n";
+        let bag = "2 yellow, 3 red".parse().unwrap();
+        assert_eq!(part_one(input, &Params { bag }), Ok(8));
+        // the bag has no yellow cubes.
+        assert_eq!(part_one(input, &Params::default()), Ok(7));
+        // game 7 shows no yellow cubes.
+        assert_eq!(part_two(input), Ok(2));
+    }
+
+    #[test]
+    fn test_reports_malformed_lines() {
+        assert_eq!(
+            part_two("Game 1: 3 blue\nGame two: 1 red\n"),
+            Err("line 2: at column 6: invalid value `two`: invalid digit found in string".into())
+        );
+        assert!(part_two("Game 1: 3 blue; red\n").is_err());
     }
 }
@@ -1,81 +1,194 @@
-advent_of_code::solution!(3);
+advent_of_code::solution!(
+    3,
+    params {
+        render: bool = false
+    }
+);
 
-use itertools::iproduct;
-use regex::Regex;
+use advent_of_code::grid::{Grid, Point};
+use advent_of_code::template::{ANSI_BOLD, ANSI_RESET};
 use std::collections::HashMap;
-use std::collections::HashSet;
-
-pub fn part_one(input: &str) -> Option<u32> {
-    let symbols_regex = Regex::new(r"[^\d\.]").unwrap();
-    let number_regex = Regex::new(r"\d+").unwrap();
-    let symbol_locations = input
-        .split('\n')
-        .enumerate()
-        .flat_map(|(row, line)| symbols_regex.find_iter(line).map(move |m| (row, m.start())));
-    let _collected_symbols: HashSet<(usize, usize)> = symbol_locations.collect();
-    let numbers = input.split('\n').enumerate().flat_map(|(row, line)| {
-        number_regex.find_iter(line).map(move |m| {
-            let value = m.as_str().parse::<u32>().unwrap();
-            (row, m.start(), m.end(), value)
-        })
-    });
-    let valid_numbers = numbers.filter_map(|(row, start, end, value)| {
-        let row_start = match row {
-            0 => 0,
-            _ => row - 1,
-        };
-        let col_start = match start {
-            0 => 0,
-            _ => start - 1,
-        };
-        for (r, c) in iproduct!(row_start..row + 2, col_start..end + 1) {
-            if _collected_symbols.contains(&(r, c)) {
-                return Some(value);
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A number of the schematic. It is a part number if it touches at least one symbol.
+pub struct Number {
+    pub value: u32,
+    /// The position of the first digit, the others follow to the right.
+    pub start: Point,
+    pub len: usize,
+    /// Indices of the touched symbols in [`Schematic::symbols`].
+    pub symbols: Vec<usize>,
+}
+
+impl Number {
+    pub fn is_part_number(&self) -> bool {
+        !self.symbols.is_empty()
+    }
+
+    fn digits(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.len as i64).map(|dx| self.start + Point::new(dx, 0))
+    }
+}
+
+/// Any character other than a digit or `.`.
+pub struct Symbol {
+    pub kind: char,
+    pub position: Point,
+    /// Indices of the touching numbers in [`Schematic::numbers`].
+    pub numbers: Vec<usize>,
+}
+
+/// The numbers and symbols of an engine schematic, linked to each other when they touch,
+/// including diagonally.
+pub struct Schematic {
+    grid: Grid<char>,
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+}
+
+impl Schematic {
+    fn parse(input: &str) -> Result<Self, String> {
+        let grid = Grid::from_str(input).map_err(|e| e.to_string())?;
+
+        let mut numbers = vec![];
+        for (y, row) in grid.rows().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let len = row[x..].iter().take_while(|c| c.is_ascii_digit()).count();
+                if len == 0 {
+                    x += 1;
+                    continue;
+                }
+                let digits: String = row[x..x + len].iter().collect();
+                let value = digits.parse().map_err(|_| {
+                    format!(
+                        "number `{digits}` at row {}, column {} is too large",
+                        y + 1,
+                        x + 1
+                    )
+                })?;
+                numbers.push(Number {
+                    value,
+                    start: Point::new(x as i64, y as i64),
+                    len,
+                    symbols: vec![],
+                });
+                x += len;
             }
         }
-        None
-    });
-    valid_numbers.reduce(|acc, e| acc + e)
-}
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let symbols_regex = Regex::new(r"[^\d\.]").unwrap();
-    let number_regex = Regex::new(r"\d+").unwrap();
-    let symbol_locations = input
-        .split('\n')
-        .enumerate()
-        .flat_map(|(row, line)| symbols_regex.find_iter(line).map(move |m| (row, m.start())));
-    let _collected_symbols: HashSet<(usize, usize)> = symbol_locations.collect();
-    let numbers = input.split('\n').enumerate().flat_map(|(row, line)| {
-        number_regex.find_iter(line).map(move |m| {
-            let value = m.as_str().parse::<u32>().unwrap();
-            (row, m.start(), m.end(), value)
-        })
-    });
-    let mut locations_to_numbers = HashMap::new();
-    for (row, start, end, value) in numbers {
-        let row_start = match row {
-            0 => 0,
-            _ => row - 1,
-        };
-        let col_start = match start {
-            0 => 0,
-            _ => start - 1,
-        };
-        for (r, c) in iproduct!(row_start..row + 2, col_start..end + 1) {
-            locations_to_numbers
-                .entry((r, c))
-                .or_insert_with(Vec::<u32>::new);
-            locations_to_numbers.get_mut(&(r, c)).unwrap().push(value);
+        let mut symbols: Vec<Symbol> = grid
+            .iter()
+            .filter(|(_, c)| **c != '.' && !c.is_ascii_digit())
+            .map(|(position, kind)| Symbol {
+                kind: *kind,
+                position,
+                numbers: vec![],
+            })
+            .collect();
+        let symbol_at: HashMap<Point, usize> = symbols
+            .iter()
+            .enumerate()
+            .map(|(idx, symbol)| (symbol.position, idx))
+            .collect();
+
+        for (number_idx, number) in numbers.iter_mut().enumerate() {
+            let mut touched: Vec<usize> = number
+                .digits()
+                .flat_map(Point::neighbours_8)
+                .filter_map(|neighbour| symbol_at.get(&neighbour).copied())
+                .collect();
+            touched.sort_unstable();
+            touched.dedup();
+
+            for symbol_idx in &touched {
+                symbols[*symbol_idx].numbers.push(number_idx);
+            }
+            number.symbols = touched;
         }
+
+        Ok(Schematic {
+            grid,
+            numbers,
+            symbols,
+        })
+    }
+
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers.iter().filter(|number| number.is_part_number())
+    }
+
+    /// The sum of the numbers that touch a symbol for which `kind` holds. A number that touches
+    /// several such symbols counts once.
+    pub fn sum_adjacent_to(&self, kind: impl Fn(char) -> bool) -> u32 {
+        self.numbers
+            .iter()
+            .filter(|number| {
+                number
+                    .symbols
+                    .iter()
+                    .any(|idx| kind(self.symbols[*idx].kind))
+            })
+            .map(|number| number.value)
+            .sum()
     }
-    let mut result = 0u32;
-    for ((row, col), group) in locations_to_numbers {
-        if _collected_symbols.contains(&(row, col)) && group.len() == 2 {
-            result += group.into_iter().product::<u32>();
+
+    /// The products of the numbers around each symbol of `kind` that touches exactly
+    /// `neighbours` numbers, e.g. the gear ratios for `*` and 2.
+    pub fn products(&self, kind: char, neighbours: usize) -> impl Iterator<Item = u64> + '_ {
+        self.symbols
+            .iter()
+            .filter(move |symbol| symbol.kind == kind && symbol.numbers.len() == neighbours)
+            .map(|symbol| {
+                symbol
+                    .numbers
+                    .iter()
+                    .map(|idx| self.numbers[*idx].value as u64)
+                    .product()
+            })
+    }
+}
+
+/// The schematic with the part numbers in bold.
+impl Display for Schematic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let part_number_at: HashMap<Point, &Number> = self
+            .part_numbers()
+            .map(|number| (number.start, number))
+            .collect();
+
+        for (y, row) in self.grid.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            let mut x = 0;
+            while x < row.len() {
+                if let Some(number) = part_number_at.get(&Point::new(x as i64, y as i64)) {
+                    let digits: String = row[x..x + number.len].iter().collect();
+                    write!(f, "{ANSI_BOLD}{digits}{ANSI_RESET}")?;
+                    x += number.len;
+                } else {
+                    write!(f, "{}", row[x])?;
+                    x += 1;
+                }
+            }
         }
+        Ok(())
     }
-    Some(result)
+}
+
+pub fn part_one(input: &str, params: &Params) -> Result<u32, String> {
+    let schematic = Schematic::parse(input)?;
+    if params.render {
+        eprintln!("{schematic}");
+    }
+    Ok(schematic.sum_adjacent_to(|_| true))
+}
+
+pub fn part_two(input: &str) -> Result<u64, String> {
+    let schematic = Schematic::parse(input)?;
+    Ok(schematic.products('*', 2).sum())
 }
 
 #[cfg(test)]
@@ -84,13 +197,69 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(4361));
+        let result = part_one(
+            &advent_of_code::template::read_file("examples", DAY),
+            &Params::default(),
+        );
+        assert_eq!(result, Ok(4361));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(467835));
+        assert_eq!(result, Ok(467835));
+    }
+
+    #[test]
+    fn test_links_numbers_and_symbols() {
+        let schematic =
+            Schematic::parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(schematic.numbers.len(), 10);
+        assert_eq!(schematic.symbols.len(), 6);
+        assert_eq!(schematic.part_numbers().count(), 8);
+
+        let star = &schematic.symbols[0];
+        assert_eq!((star.kind, star.position), ('*', Point::new(3, 1)));
+        let around: Vec<u32> = star
+            .numbers
+            .iter()
+            .map(|idx| schematic.numbers[*idx].value)
+            .collect();
+        assert_eq!(around, [467, 35]);
+
+        assert_eq!(schematic.sum_adjacent_to(|kind| kind == '#'), 633);
+        assert_eq!(
+            schematic.sum_adjacent_to(|kind| kind == '*'),
+            467 + 35 + 617 + 755 + 598
+        );
+        assert_eq!(schematic.products('*', 1).collect::<Vec<_>>(), [617]);
+        assert_eq!(schematic.products('$', 1).collect::<Vec<_>>(), [664]);
+    }
+
+    #[test]
+    fn test_numbers_touching_several_symbols() {
+        // 12 touches both symbols but counts once, 3 touches the second symbol only.
+        let schematic = Schematic::parse("*..\n12#\n..3\n").unwrap();
+        assert_eq!(schematic.sum_adjacent_to(|_| true), 15);
+        assert_eq!(schematic.products('#', 2).collect::<Vec<_>>(), [36]);
+        assert_eq!(schematic.numbers[0].symbols, [0, 1]);
+    }
+
+    #[test]
+    fn test_render() {
+        let schematic = Schematic::parse("467..114..\n...*......\n").unwrap();
+        assert_eq!(
+            schematic.to_string(),
+            format!("{ANSI_BOLD}467{ANSI_RESET}..114..\n...*......")
+        );
+    }
+
+    #[test]
+    fn test_reports_malformed_input() {
+        assert!(Schematic::parse("..1\n.*\n").is_err());
+        assert_eq!(
+            part_two("*99999999999\n").err(),
+            Some("number `99999999999` at row 1, column 2 is too large".to_string())
+        );
     }
 }
@@ -16,9 +16,7 @@ pub fn part_one(input: &str) -> Option<u32> {
             cap.name("numbers_you_have").unwrap().as_str(),
         )
     });
-    num_winners
-        .map(score_num_winners)
-        .reduce(|acc, e| (acc + e))
+    num_winners.map(score_num_winners).reduce(|acc, e| acc + e)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
@@ -45,7 +43,7 @@ pub fn part_two(input: &str) -> Option<u32> {
             repeats[other_idx] += repeats[idx];
         }
     }
-    repeats.into_iter().reduce(|acc, e| (acc + e))
+    repeats.into_iter().reduce(|acc, e| acc + e)
 }
 
 fn count_winners(number_regex: &Regex, winning_numbers: &str, numbers_you_have: &str) -> u32 {
@@ -1,186 +1,277 @@
-advent_of_code::solution!(5);
-
-use regex::Regex;
-use std::cmp::max;
-use std::cmp::min;
-use std::ops::Range;
-use std::str::FromStr;
-struct MappingSlice {
-    index_from: u32,
-    index_to: u32,
-    len: u32,
-}
+advent_of_code::solution!(5, params { dump: bool = false });
+
+use advent_of_code::interval::{Interval, IntervalMap, IntervalSet};
+use advent_of_code::parse::{blocks, unsigned};
+use std::fmt::Display;
 
-fn retrieve_unwrap_parse<T: FromStr>(captures: &regex::Captures, name: &str) -> T
-where
-    <T as FromStr>::Err: std::fmt::Debug,
-{
-    captures.name(name).unwrap().as_str().parse::<T>().unwrap()
+/*
+Every map of the almanac shifts some ranges of numbers and keeps all others, so it is a
+piecewise-linear function. Chaining them yields another piecewise-linear function, which
+maps seeds to locations directly and is computed once while parsing.
+*/
+struct Almanac {
+    seeds: Vec<u64>,
+    stages: Vec<(String, IntervalMap<u64>)>,
+    seed_to_location: IntervalMap<u64>,
 }
 
-fn parse(input: &str) -> (Vec<u32>, Vec<Vec<MappingSlice>>) {
-    let seeds_regex = Regex::new(r"seeds: .*\n").unwrap();
-    let map_regex =
-        Regex::new(r"(?<map_declaration>\S+ map:)\n(?<map_elements>(?:\d+ \d+ \d+\n)+)").unwrap();
-    let number_regex = Regex::new(r"\d+").unwrap();
-    let map_numbers_regex = Regex::new(r"(?<idx_to>\d+) (?<idx_from>\d+) (?<len>\d+)").unwrap();
-
-    let seeds_info = seeds_regex.find(input).unwrap().as_str().trim();
-    let seeds = number_regex
-        .find_iter(seeds_info)
-        .map(|m| m.as_str().parse::<u32>().unwrap())
-        .collect();
-
-    let map_info = map_regex
-        .captures_iter(input)
-        .map(|m| m.name("map_elements").unwrap().as_str());
-    let maps: Vec<Vec<MappingSlice>> = map_info
-        .map(|m| {
-            m.trim()
-                .split('\n')
-                .map(|s| {
-                    let cap = map_numbers_regex.captures(s).unwrap();
-                    MappingSlice {
-                        index_from: retrieve_unwrap_parse::<u32>(&cap, "idx_from"),
-                        index_to: retrieve_unwrap_parse::<u32>(&cap, "idx_to"),
-                        len: retrieve_unwrap_parse::<u32>(&cap, "len"),
-                    }
-                })
-                .collect()
+impl Almanac {
+    fn parse(input: &str) -> Result<Self, String> {
+        let mut blocks = blocks(input);
+        let seeds = unsigned(blocks.next().ok_or("the almanac is empty")?)
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("seeds {e}"))?;
+
+        let stages: Vec<(String, IntervalMap<u64>)> = blocks
+            .map(|block| {
+                let (name, mapping_slices) = block.split_once('\n').unwrap_or((block, ""));
+                let mut map = IntervalMap::new();
+                for line in mapping_slices.lines() {
+                    let numbers: Vec<u64> = unsigned(line)
+                        .collect::<Result<_, _>>()
+                        .map_err(|e| format!("mapping slice `{line}` {e}"))?;
+                    let [index_to, index_from, len] = numbers[..] else {
+                        return Err(format!("invalid mapping slice `{line}`"));
+                    };
+                    map.insert_range(index_to, index_from, len)
+                        .map_err(|_| format!("mapping slice `{line}` extends beyond u64"))?;
+                }
+                Ok((name.trim_end_matches(" map:").to_string(), map))
+            })
+            .collect::<Result<_, String>>()?;
+
+        let seed_to_location = stages
+            .iter()
+            .fold(IntervalMap::identity(), |composed, (_, map)| {
+                composed.then(map)
+            });
+
+        Ok(Almanac {
+            seeds,
+            stages,
+            seed_to_location,
         })
-        .collect();
-    (seeds, maps)
-}
+    }
 
-fn try_apply_mapping_slice(current: u32, mapping_slice: &MappingSlice) -> Option<u32> {
-    let start = mapping_slice.index_from;
-    let end = start.checked_add(mapping_slice.len - 1).unwrap_or(u32::MAX);
-    let range = start..=end;
-    match range.contains(&current) {
-        true => Some(current - mapping_slice.index_from + mapping_slice.index_to),
-        _ => None,
+    fn location(&self, seed: u64) -> u64 {
+        // every destination range lies within u64, so no seed leaves it.
+        self.seed_to_location.apply(seed).unwrap()
     }
-}
 
-fn apply_mapping(current: u32, mapping: &[MappingSlice]) -> u32 {
-    let retrieved_result = mapping
-        .iter()
-        .filter_map(|mapping_slice| try_apply_mapping_slice(current, mapping_slice))
-        .next();
-    retrieved_result.unwrap_or(current)
-}
+    fn locations(&self, seeds: &IntervalSet<u64>) -> IntervalSet<u64> {
+        self.seed_to_location.apply_set(seeds)
+    }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (seeds, maps) = parse(input);
-    // mapped_seeds
-    seeds
-        .iter()
-        .map(|seed| {
-            maps.iter().fold(*seed, |current, mapping| {
-                apply_mapping(current, &mapping[..])
+    /// The seeds that end up at one of the `locations`.
+    fn seeds_for(&self, locations: &IntervalSet<u64>) -> IntervalSet<u64> {
+        self.seed_to_location.preimage(locations)
+    }
+
+    /// The seeds of part two, read as pairs of start and length.
+    fn seed_ranges(&self) -> Result<IntervalSet<u64>, String> {
+        self.seeds
+            .chunks(2)
+            .map(|pair| match *pair {
+                [start, len] => Interval::with_len(start, len)
+                    .ok_or_else(|| format!("seed range `{start} {len}` extends beyond u64")),
+                _ => Err(format!("seed `{}` has no range length", pair[0])),
             })
-        })
-        .min()
+            .collect()
+    }
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let (seed_inputs, maps) = parse(input);
-    let (seed_starts, seed_lengths): (_, Vec<_>) = seed_inputs
-        .iter()
-        .enumerate()
-        .partition(|(idx, _val)| (idx % 2) == 0);
-    // Modify the previously read into seed ranges
-    let seed_ranges: Vec<Range<u32>> = seed_starts
-        .iter()
-        .zip(seed_lengths.iter())
-        .map(|((_, start), (_, length))|
-                // TODO: ADDRESS OVERFLOW
-                (**start)..(**start + **length))
-        .collect();
-    // Pass each seed range through all the mappings
-    let mapped_seeds: Vec<Range<u32>> = seed_ranges
-        .iter()
-        .flat_map(|seed_range| {
-            #[allow(clippy::single_range_in_vec_init)]
-            let start = vec![seed_range.start..seed_range.end];
-            maps.iter().fold(start, |ranges, mapping| {
-                apply_range_mapping(&ranges, mapping)
-            })
-        })
-        .collect();
-    let min = mapped_seeds.iter().map(|range| range.start).min();
-    min
+/// A dump of the composed map, one line per range of seeds that is shifted.
+impl Display for Almanac {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<&str> = self.stages.iter().map(|(name, _)| name.as_str()).collect();
+        writeln!(
+            f,
+            "{} ({} pieces)",
+            names.join(", "),
+            self.seed_to_location.pieces().len()
+        )?;
+        for (seeds, delta) in self.seed_to_location.pieces() {
+            writeln!(
+                f,
+                "  seeds {seeds} -> locations {} ({delta:+})",
+                seeds.shift(*delta)
+            )?;
+        }
+        write!(f, "  all other seeds keep their number")
+    }
 }
 
-fn apply_range_mapping(ranges: &[Range<u32>], mapping: &[MappingSlice]) -> Vec<Range<u32>> {
-    ranges
+pub fn part_one(input: &str, params: &Params) -> Result<u64, String> {
+    let almanac = Almanac::parse(input)?;
+    if params.dump {
+        eprintln!("{almanac}");
+    }
+    almanac
+        .seeds
         .iter()
-        .flat_map(|range| {
-            #[allow(clippy::single_range_in_vec_init)]
-            let start = vec![range.start..range.end];
-            let mut leftovers = mapping.iter().fold(start, |remainder, slice| {
-                remainder
-                    .iter()
-                    .flat_map(|remainder_range| {
-                        let slice_start = slice.index_from;
-                        let slice_end = slice_start.checked_add(slice.len).unwrap_or(u32::MAX);
-                        let beneath: Range<u32> =
-                            remainder_range.start..min(slice_start, remainder_range.end - 1);
-                        let above: Range<u32> =
-                            (max(slice_end, remainder_range.start))..remainder_range.end;
-
-                        if !beneath.is_empty() && !above.is_empty() {
-                            vec![beneath, above]
-                        } else if !beneath.is_empty() {
-                            vec![beneath]
-                        } else if !above.is_empty() {
-                            vec![above]
-                        } else {
-                            vec![]
-                        }
-                    })
-                    .collect()
-            });
-            let mut mapped: Vec<Range<u32>> = mapping
-                .iter()
-                .filter_map(|slice| {
-                    let slice_start = slice.index_from;
-                    let slice_end = slice_start.checked_add(slice.len - 1).unwrap_or(u32::MAX);
-                    let intersection: Range<u32> =
-                        max(slice_start, range.start)..min(slice_end, range.end);
-                    if intersection.is_empty() {
-                        None
-                    } else if slice.index_to >= slice.index_from {
-                        let offset = slice.index_to - slice.index_from;
-                        Some((offset + intersection.start)..(offset + intersection.end))
-                    } else {
-                        let negative_offset = slice.index_from - slice.index_to;
-                        Some(
-                            (intersection.start - negative_offset)
-                                ..(intersection.end - negative_offset),
-                        )
-                    }
-                })
-                .collect();
-            leftovers.append(&mut mapped);
-            leftovers
-        })
-        .collect()
+        .map(|seed| almanac.location(*seed))
+        .min()
+        .ok_or_else(|| "the almanac lists no seeds".to_string())
+}
+
+pub fn part_two(input: &str, params: &Params) -> Result<u64, String> {
+    let almanac = Almanac::parse(input)?;
+    let seed_ranges = almanac.seed_ranges()?;
+    let closest = almanac
+        .locations(&seed_ranges)
+        .min()
+        .ok_or("the almanac lists no seeds")?;
+    if params.dump {
+        let location = IntervalSet::from(Interval::inclusive(closest, closest));
+        let seeds = almanac.seeds_for(&location).intersection(&seed_ranges);
+        eprintln!("location {closest} is reached by seeds {seeds}");
+    }
+    Ok(closest)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const NEAR_U32_MAX: &str = "seeds: 4294967294 10
+
+seed-to-soil map:
+0 4294967290 6
+
+soil-to-fertilizer map:
+4294967295 5 1
+";
+
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(35));
+        let result = part_one(
+            &advent_of_code::template::read_file("examples", DAY),
+            &Params::default(),
+        );
+        assert_eq!(result, Ok(35));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(46));
+        let result = part_two(
+            &advent_of_code::template::read_file("examples", DAY),
+            &Params::default(),
+        );
+        assert_eq!(result, Ok(46));
+    }
+
+    #[test]
+    fn test_composed_map_matches_stages() {
+        let almanac =
+            Almanac::parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        for seed in 0..200 {
+            let expected = almanac
+                .stages
+                .iter()
+                .fold(seed, |current, (_, map)| map.apply(current).unwrap());
+            assert_eq!(almanac.location(seed), expected, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_reverse_lookup() {
+        let almanac =
+            Almanac::parse(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let seeds = almanac.seeds_for(&IntervalSet::from(Interval::new(35, 36)));
+        assert!(seeds.contains(13));
+        assert!(seeds.intervals().iter().all(|range| {
+            let (first, last) = (range.first().unwrap(), range.last().unwrap());
+            (first..=last).all(|seed| almanac.location(seed) == 35)
+        }));
+
+        let seed_ranges = almanac.seed_ranges().unwrap();
+        let closest = almanac.seeds_for(&IntervalSet::from(Interval::new(46, 47)));
+        assert!(!closest.intersection(&seed_ranges).is_empty());
+    }
+
+    #[test]
+    fn test_ranges_touching_u32_max() {
+        let almanac = Almanac::parse(NEAR_U32_MAX).unwrap();
+        // seed 4294967295 is soil 5, which is fertilizer 4294967295.
+        assert_eq!(almanac.location(4294967295), 4294967295);
+        assert_eq!(almanac.location(4294967296), 4294967296);
+        assert_eq!(almanac.location(4294967291), 1);
+
+        let params = Params::default();
+        assert_eq!(part_one(NEAR_U32_MAX, &params), Ok(4));
+        assert_eq!(part_two(NEAR_U32_MAX, &params), Ok(4));
+
+        let locations = almanac.locations(&almanac.seed_ranges().unwrap());
+        assert_eq!(locations.len(), 10);
+        assert!(locations.contains(u32::MAX as u64));
+        assert!(locations.contains(u32::MAX as u64 + 4));
+    }
+
+    #[test]
+    fn test_dump() {
+        let almanac = Almanac::parse(NEAR_U32_MAX).unwrap();
+        assert_eq!(
+            almanac.to_string(),
+            "seed-to-soil, soil-to-fertilizer (2 pieces)
+  seeds [5, 6) -> locations [4294967295, 4294967296) (+4294967290)
+  seeds [4294967290, 4294967295) -> locations [0, 5) (-4294967290)
+  all other seeds keep their number"
+        );
+    }
+
+    #[test]
+    fn test_ranges_touching_u64_max() {
+        let input = "seeds: 18446744073709551606 10
+
+seed-to-soil map:
+0 18446744073709551610 6
+";
+        let params = Params::default();
+        assert_eq!(part_one(input, &params), Ok(10));
+        assert_eq!(part_two(input, &params), Ok(0));
+
+        let almanac = Almanac::parse(input).unwrap();
+        assert_eq!(almanac.location(u64::MAX), 5);
+        assert!(almanac.seed_ranges().unwrap().contains(u64::MAX));
+    }
+
+    #[test]
+    fn test_reports_invalid_almanacs() {
+        let params = Params::default();
+        assert_eq!(
+            part_one(
+                "seeds: 1 2\n\nseed-to-soil map:\n0 18446744073709551610 7\n",
+                &params
+            ),
+            Err("mapping slice `0 18446744073709551610 7` extends beyond u64".to_string())
+        );
+        assert_eq!(
+            part_one("seeds: 1 2\n\nseed-to-soil map:\n0 1\n", &params),
+            Err("invalid mapping slice `0 1`".to_string())
+        );
+        assert_eq!(
+            part_two("seeds: 18446744073709551606 11\n", &params),
+            Err("seed range `18446744073709551606 11` extends beyond u64".to_string())
+        );
+        assert_eq!(
+            part_two("seeds: 1 2 3\n", &params),
+            Err("seed `3` has no range length".to_string())
+        );
+        assert_eq!(
+            part_one("", &params),
+            Err("the almanac is empty".to_string())
+        );
+        assert_eq!(
+            part_one("seeds: 18446744073709551616\n", &params),
+            Err(
+                "seeds at column 8: integer `18446744073709551616` does not fit into u64."
+                    .to_string()
+            )
+        );
+        assert!(part_one(
+            "seeds: 1\n\nseed-to-soil map:\n0 1 99999999999999999999\n",
+            &params
+        )
+        .is_err());
     }
 }
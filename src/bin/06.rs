@@ -1,20 +1,21 @@
 advent_of_code::solution!(6);
 
+use advent_of_code::math::{bounds_len, quadratic_below_zero};
 use regex::Regex;
 
-pub fn part_one(input: &str) -> Option<u32> {
+pub fn part_one(input: &str) -> Option<u64> {
     let time_regex = Regex::new(r"Time:\s*(?:\d+\s+)+").unwrap();
     let distance_regex = Regex::new(r"Distance:\s*(?:\d+\s+)+").unwrap();
     let number_regex = Regex::new(r"\d+").unwrap();
     let time_line = time_regex.find(input).unwrap().as_str();
     let distance_line = distance_regex.find(input).unwrap().as_str();
-    let all_times: Vec<u32> = number_regex
+    let all_times: Vec<u64> = number_regex
         .find_iter(time_line)
-        .map(|m| m.as_str().parse::<u32>().unwrap())
+        .map(|m| m.as_str().parse::<u64>().unwrap())
         .collect();
-    let all_distances: Vec<u32> = number_regex
+    let all_distances: Vec<u64> = number_regex
         .find_iter(distance_line)
-        .map(|m| m.as_str().parse::<u32>().unwrap())
+        .map(|m| m.as_str().parse::<u64>().unwrap())
         .collect();
 
     /*
@@ -56,37 +57,23 @@ pub fn part_one(input: &str) -> Option<u32> {
     t2 = (T + sqrt(T^2 - 4D)) / 2
 
     So all times in between (t1, t2) are the solution.
+    Floating point roots are not exact for long races, so the integer bounds are computed with an
+    integer square root and corrected by evaluating t^2 - T * t + D < 0 at the boundaries.
     */
-    let all_results: Vec<u32> = all_times
+    let result = all_times
         .iter()
         .zip(all_distances.iter())
-        .map(|(t, d)| solve(&(*t as u64), &(*d as u64)))
-        .map(|x| x as u32)
-        .collect();
-    let result = all_results.iter().product::<u32>();
+        .map(|(t, d)| solve(t, d))
+        .product::<u64>();
     Some(result)
 }
 
-fn quadratic_roots(a: f64, b: f64, c: f64) -> (f64, f64) {
-    let r1 = (-b + (b.powi(2) - 4f64 * a * c).sqrt()) / (2f64 * a);
-    let r2 = (-b - (b.powi(2) - 4f64 * a * c).sqrt()) / (2f64 * a);
-    (r1, r2)
-}
-
 fn solve(race_time: &u64, distance_to_beat: &u64) -> u64 {
-    let (r1, r2) = quadratic_roots(-1f64, *race_time as f64, -(*distance_to_beat as f64));
-    let lower_bound = match r1.fract() {
-        x if x == 0f64 => r1.ceil() as i64 + 1,
-        _ => r1.ceil() as i64,
-    };
-    let upper_bound = match r2.fract() {
-        x if x == 0f64 => r2.floor() as i64 - 1,
-        _ => r2.floor() as i64,
-    };
-    (upper_bound - lower_bound + 1) as u64
+    let bounds = quadratic_below_zero(1, -(*race_time as i128), *distance_to_beat as i128);
+    bounds_len(bounds) as u64
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(input: &str) -> Option<u64> {
     let time_regex = Regex::new(r"Time:\s*(?:\d+\s+)+").unwrap();
     let distance_regex = Regex::new(r"Distance:\s*(?:\d+\s+)+").unwrap();
     let number_regex = Regex::new(r"\d+").unwrap();
@@ -104,7 +91,7 @@ pub fn part_two(input: &str) -> Option<u32> {
     let actual_time = all_times.join("").parse::<u64>().unwrap();
     let actual_distance_to_beat = all_distances.join("").parse::<u64>().unwrap();
     let result = solve(&actual_time, &actual_distance_to_beat);
-    Some(result as u32)
+    Some(result)
 }
 
 #[cfg(test)]
@@ -122,4 +109,43 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(71503));
     }
+
+    #[test]
+    fn test_perfect_square_discriminants() {
+        // the roots are integers, which only tie the record and do not beat it.
+        assert_eq!(solve(&30, &200), 9);
+        assert_eq!(solve(&10, &24), 1);
+        assert_eq!(solve(&10, &25), 0);
+        assert_eq!(solve(&7, &0), 6);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for race_time in 0..60u64 {
+            for distance_to_beat in 0..race_time * race_time / 4 + 2 {
+                let expected = (0..=race_time)
+                    .filter(|t| t * (race_time - t) > distance_to_beat)
+                    .count() as u64;
+                assert_eq!(solve(&race_time, &distance_to_beat), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_long_races() {
+        // where f64 can no longer represent every time and distance.
+        let race_time = 6_000_000_000u64;
+        let half = race_time / 2;
+        assert_eq!(solve(&race_time, &(half * half - 1)), 1);
+        assert_eq!(solve(&race_time, &(half * half - 2)), 3);
+        assert_eq!(solve(&race_time, &((half - 2) * (half + 2))), 3);
+        assert_eq!(solve(&race_time, &((half - 2) * (half + 2) - 1)), 5);
+        assert_eq!(solve(&race_time, &0), race_time - 1);
+
+        let race_time = i64::MAX as u64;
+        assert_eq!(solve(&race_time, &(race_time - 1)), race_time - 3);
+
+        let input = "Time: 71530 4294967296\nDistance: 940200 0\n";
+        assert_eq!(part_one(input), Some(71503 * 4294967295));
+    }
 }
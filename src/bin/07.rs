@@ -1,148 +1,141 @@
 advent_of_code::solution!(7);
 
-use std::collections::HashMap;
-
-use itertools::Itertools;
-use regex::Regex;
-
-pub fn part_one(input: &str) -> Option<u32> {
-    let re = Regex::new(r"(?<hand>\w{5}) (?<bet>\d+)").unwrap();
-    let (hands, bets): (Vec<&str>, Vec<u32>) = re
-        .captures_iter(input)
-        .map(|cap| {
-            (
-                cap.name("hand").unwrap().as_str(),
-                cap.name("bet").unwrap().as_str().parse::<u32>().unwrap(),
-            )
-        })
-        .unzip();
-    let hand_scores = hands.iter().map(hand_score).collect_vec();
-    let indices_ranked: Vec<usize> = hand_scores
-        .iter()
-        .enumerate()
-        .sorted_by_key(|(_idx, score)| *score)
-        .map(|(old_idx, _score)| old_idx)
-        .collect();
-    let results: Vec<u32> = indices_ranked
-        .iter()
-        .enumerate()
-        .map(|(rank, old_idx)| (rank as u32 + 1) * bets.get(*old_idx).unwrap())
-        .collect();
-    let result = results.iter().sum();
-    Some(result)
-}
+use advent_of_code::parse::{lines, scan};
+use std::cmp::Reverse;
 
-fn hand_score(hand: &&str) -> (u32, u32) {
-    let gs = type_score(hand);
-    let tbs = tie_breaker_score(hand);
-    (gs, tbs)
+/// How hands are built and compared.
+#[derive(Debug, Clone, Copy)]
+pub struct Ruleset {
+    /// All cards, from the weakest to the strongest.
+    pub cards: &'static str,
+    /// A card that joins whichever group makes the hand strongest, e.g. the joker of part two.
+    /// It only takes its own place in `cards` when breaking ties.
+    pub wildcard: Option<char>,
+    pub hand_size: usize,
 }
 
-fn type_score(hand: &&str) -> u32 {
-    let mut card_counts: HashMap<char, u32> = HashMap::new();
-    hand.chars().for_each(|c| {
-        card_counts.insert(c, *card_counts.get(&c).unwrap_or(&0) + 1);
-    });
-    let repeat_counts: Vec<&u32> = card_counts.values().sorted().rev().collect();
-    match repeat_counts[..] {
-        [2, 1, 1, 1] => 1,
-        [2, 2, 1] => 2,
-        [3, 1, 1] => 3,
-        [3, 2] => 4,
-        [4, 1] => 5,
-        [5] => 6,
-        _ => 0,
+impl Ruleset {
+    pub const STANDARD: Ruleset = Ruleset {
+        cards: "23456789TJQKA",
+        wildcard: None,
+        hand_size: 5,
+    };
+
+    pub const JOKERS: Ruleset = Ruleset {
+        cards: "J23456789TQKA",
+        wildcard: Some('J'),
+        hand_size: 5,
+    };
+
+    pub fn hand(&self, s: &str) -> Result<Hand, String> {
+        let cards = s
+            .chars()
+            .map(|card| {
+                self.cards
+                    .chars()
+                    .position(|c| c == card)
+                    .map(|strength| strength as u8)
+                    .ok_or_else(|| format!("unknown card `{card}` in hand `{s}`"))
+            })
+            .collect::<Result<Vec<u8>, String>>()?;
+        if cards.len() != self.hand_size {
+            return Err(format!(
+                "hand `{s}` has {} cards, expected {}",
+                cards.len(),
+                self.hand_size
+            ));
+        }
+
+        let wildcard = self
+            .wildcard
+            .and_then(|card| self.cards.chars().position(|c| c == card))
+            .map(|strength| strength as u8);
+
+        let mut counts = vec![0u8; self.cards.chars().count()];
+        let mut wildcards = 0;
+        for card in &cards {
+            if Some(*card) == wildcard {
+                wildcards += 1;
+            } else {
+                counts[*card as usize] += 1;
+            }
+        }
+
+        let mut groups: Vec<u8> = counts.into_iter().filter(|count| *count > 0).collect();
+        groups.sort_unstable_by_key(|count| Reverse(*count));
+        // wildcards are best spent on the largest group.
+        match groups.first_mut() {
+            Some(largest) => *largest += wildcards,
+            None => groups.push(wildcards),
+        }
+
+        Ok(Hand { groups, cards })
     }
 }
 
-fn tie_breaker_score(hand: &&str) -> u32 {
-    const POWERS: [u32; 5] = [
-        50625, // 15^4
-        3375,  // 15^3
-        225,   // 15^2
-        15,    // 15^1
-        1,     // 15^0
-    ];
-
-    hand.char_indices()
-        .map(|(i, c)| POWERS.get(i).unwrap() * card_score(c))
-        .sum()
+/// The type of a hand, from the weakest to the strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+/// A hand under a [`Ruleset`]. Hands compare by their groups of equal cards first, then card by
+/// card. Only hands of the same ruleset are comparable.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hand {
+    /// The sizes of the groups of equal cards, largest first.
+    groups: Vec<u8>,
+    /// The strengths of the cards in their original order.
+    cards: Vec<u8>,
 }
 
-fn card_score(card: char) -> u32 {
-    match card {
-        'T' => 10,
-        'J' => 11,
-        'Q' => 12,
-        'K' => 13,
-        'A' => 14,
-        c => c.to_digit(10).unwrap_or_default(),
+impl Hand {
+    /// The category of the two largest groups. Larger hands may contain several groups of the same
+    /// category, which are still ranked by [`Ord`].
+    pub fn category(&self) -> Category {
+        let largest = self.groups[0];
+        let second = self.groups.get(1).copied().unwrap_or(0);
+        match (largest, second) {
+            (5.., _) => Category::FiveOfAKind,
+            (4, _) => Category::FourOfAKind,
+            (3, 2..) => Category::FullHouse,
+            (3, _) => Category::ThreeOfAKind,
+            (2, 2) => Category::TwoPair,
+            (2, _) => Category::OnePair,
+            _ => Category::HighCard,
+        }
     }
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let re = Regex::new(r"(?<hand>\w{5}) (?<bet>\d+)").unwrap();
-    let (hands, bets): (Vec<&str>, Vec<u32>) = re
-        .captures_iter(input)
-        .map(|cap| {
-            (
-                cap.name("hand").unwrap().as_str(),
-                cap.name("bet").unwrap().as_str().parse::<u32>().unwrap(),
-            )
+/// The sum of the bids, each multiplied by the rank of its hand (1 for the weakest).
+fn total_winnings(input: &str, ruleset: &Ruleset) -> Result<u64, String> {
+    let mut hands = lines(input)
+        .map(|line| {
+            let (hand, bid): (&str, u64) = scan("{} {}", line).map_err(|e| e.to_string())?;
+            Ok((ruleset.hand(hand)?, bid))
         })
-        .unzip();
-    let hand_scores = hands.iter().map(hand_score_with_joker).collect_vec();
-    let indices_ranked: Vec<usize> = hand_scores
-        .iter()
-        .enumerate()
-        .sorted_by_key(|(_idx, score)| *score)
-        .map(|(old_idx, _score)| old_idx)
-        .collect();
-    let results: Vec<u32> = indices_ranked
+        .collect::<Result<Vec<(Hand, u64)>, String>>()?;
+    hands.sort_unstable();
+
+    Ok(hands
         .iter()
-        .enumerate()
-        .map(|(rank, old_idx)| (rank as u32 + 1) * bets.get(*old_idx).unwrap())
-        .collect();
-    let result = results.iter().sum();
-    Some(result)
+        .zip(1..)
+        .map(|((_, bid), rank)| rank * bid)
+        .sum())
 }
 
-fn hand_score_with_joker(hand: &&str) -> (u32, u32) {
-    let gs = type_score_with_joker(hand);
-    let hand_with_jokers_replaced = hand.replace('J', "1");
-    let tbs = tie_breaker_score(&&hand_with_jokers_replaced[..]);
-    (gs, tbs)
+pub fn part_one(input: &str) -> Result<u64, String> {
+    total_winnings(input, &Ruleset::STANDARD)
 }
 
-fn type_score_with_joker(hand: &&str) -> u32 {
-    let mut card_counts: HashMap<char, u32> = HashMap::new();
-    hand.chars().for_each(|c| {
-        card_counts.insert(c, *card_counts.get(&c).unwrap_or(&0) + 1);
-    });
-    if card_counts.contains_key(&'J') && card_counts.len() > 1 {
-        // Replace the top non-joker card
-        let top_char = card_counts
-            .iter()
-            .filter(|(k, _v)| **k != 'J')
-            .max_by_key(|(_k, v)| **v)
-            .unwrap()
-            .0;
-        card_counts.insert(
-            *top_char,
-            *card_counts.get(top_char).unwrap() + *card_counts.get(&'J').unwrap(),
-        );
-        card_counts.remove(&'J');
-    }
-    let repeat_counts: Vec<&u32> = card_counts.values().sorted().rev().collect();
-    match repeat_counts[..] {
-        [2, 1, 1, 1] => 1,
-        [2, 2, 1] => 2,
-        [3, 1, 1] => 3,
-        [3, 2] => 4,
-        [4, 1] => 5,
-        [5] => 6,
-        _ => 0,
-    }
+pub fn part_two(input: &str) -> Result<u64, String> {
+    total_winnings(input, &Ruleset::JOKERS)
 }
 
 #[cfg(test)]
@@ -152,12 +145,132 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(6440));
+        assert_eq!(result, Ok(6440));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(5905));
+        assert_eq!(result, Ok(5905));
+    }
+
+    /// Asserts the categories of `hands` and that each one beats the one before.
+    fn assert_ranking(ruleset: &Ruleset, hands: &[(&str, Category)]) {
+        let parsed: Vec<Hand> = hands
+            .iter()
+            .map(|(hand, category)| {
+                let parsed = ruleset.hand(hand).unwrap();
+                assert_eq!(parsed.category(), *category, "hand {hand}");
+                parsed
+            })
+            .collect();
+        for (idx, pair) in parsed.windows(2).enumerate() {
+            assert!(
+                pair[0] < pair[1],
+                "{} should beat {}",
+                hands[idx + 1].0,
+                hands[idx].0
+            );
+        }
+    }
+
+    #[test]
+    fn test_categories() {
+        assert_ranking(
+            &Ruleset::STANDARD,
+            &[
+                ("AKQJT", Category::HighCard),
+                ("2234J", Category::OnePair),
+                ("2233J", Category::TwoPair),
+                ("2223J", Category::ThreeOfAKind),
+                ("222JJ", Category::FullHouse),
+                ("2222J", Category::FourOfAKind),
+                ("JJJJJ", Category::FiveOfAKind),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_categories_with_jokers() {
+        // two pairs never use a joker, which would rather make three of a kind.
+        assert_ranking(
+            &Ruleset::JOKERS,
+            &[
+                ("AKQT9", Category::HighCard),
+                ("2345J", Category::OnePair),
+                ("AAKK2", Category::TwoPair),
+                ("J23JA", Category::ThreeOfAKind),
+                ("2234J", Category::ThreeOfAKind),
+                ("2233J", Category::FullHouse),
+                ("2JJJ3", Category::FourOfAKind),
+                ("2223J", Category::FourOfAKind),
+                ("JJJJJ", Category::FiveOfAKind),
+                ("222JJ", Category::FiveOfAKind),
+                ("22222", Category::FiveOfAKind),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_tie_breaks() {
+        let standard = Ruleset::STANDARD;
+        assert!(standard.hand("33332").unwrap() > standard.hand("2AAAA").unwrap());
+        assert!(standard.hand("KK677").unwrap() > standard.hand("KTJJT").unwrap());
+
+        // the joker is the weakest card when breaking ties.
+        let jokers = Ruleset::JOKERS;
+        assert!(jokers.hand("JKKK2").unwrap() < jokers.hand("QQQQ2").unwrap());
+        assert!(jokers.hand("KTJJT").unwrap() > jokers.hand("QQQJA").unwrap());
+        assert!(jokers.hand("J2222").unwrap() < jokers.hand("2222J").unwrap());
+    }
+
+    #[test]
+    fn test_other_rulesets() {
+        let small = Ruleset {
+            cards: "123*",
+            wildcard: Some('*'),
+            hand_size: 3,
+        };
+        assert_ranking(
+            &small,
+            &[
+                ("321", Category::HighCard),
+                ("113", Category::OnePair),
+                ("22*", Category::ThreeOfAKind),
+                ("333", Category::ThreeOfAKind),
+                ("***", Category::ThreeOfAKind),
+            ],
+        );
+        assert_eq!(total_winnings("113 10\n321 1\n", &small), Ok(21));
+
+        let large = Ruleset {
+            hand_size: 7,
+            ..Ruleset::STANDARD
+        };
+        assert_ranking(
+            &large,
+            &[
+                ("AAKKQQJ", Category::TwoPair),
+                ("22233AK", Category::FullHouse),
+                ("222333A", Category::FullHouse),
+                ("2222345", Category::FourOfAKind),
+                ("2222335", Category::FourOfAKind),
+                ("2222233", Category::FiveOfAKind),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_reports_invalid_hands() {
+        let standard = Ruleset::STANDARD;
+        assert_eq!(
+            standard.hand("AAAA").err(),
+            Some("hand `AAAA` has 4 cards, expected 5".to_string())
+        );
+        assert_eq!(
+            standard.hand("AAAA1").err(),
+            Some("unknown card `1` in hand `AAAA1`".to_string())
+        );
+        assert!(part_two("32T3K 765\nKK677 x\n").is_err());
     }
 }
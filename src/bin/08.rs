@@ -1,5 +1,6 @@
 advent_of_code::solution!(8);
 
+use advent_of_code::math::{crt, lcm_all};
 use itertools::FoldWhile::{Continue, Done};
 use itertools::Itertools;
 use regex::Regex;
@@ -52,7 +53,7 @@ pub fn part_one(input: &str) -> Option<u32> {
     }
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(input: &str) -> Result<u64, String> {
     let instructions_re = Regex::new(r"^[LR]+").unwrap();
     let instructions = instructions_re.find(input).unwrap().as_str();
     let nodes_re = Regex::new(r"(?<from>\w{3}) = \((?<L>\w{3}), (?<R>\w{3})\)").unwrap();
@@ -80,71 +81,161 @@ pub fn part_two(input: &str) -> Option<u32> {
         .map(|(from, _left, _right)| *from)
         .filter(|node| node.ends_with('A'))
         .collect();
-    let mut short_map: HashMap<(&str, usize), (&str, usize)> = HashMap::new();
+    let mut short_map: HashMap<(&str, usize), Option<(&str, usize)>> = HashMap::new();
     // Pile for the nodes / time points we'll explore.
     // We start with the start nodes at t = 0
     let mut pile: Vec<(&str, usize)> = nodes_for_start.iter().map(|&n| (n, 0)).collect();
-    let mut seen: HashSet<(&str, usize)> = HashSet::new();
+    let mut seen: HashSet<(&str, usize)> = pile.iter().copied().collect();
     while let Some((start, offset)) = pile.pop() {
-        let (end, steps) = atoz(
-            start,
-            offset % instructions.len(),
-            instructions,
-            &left_map,
-            &right_map,
-        );
-        short_map.insert((start, offset), (end, steps));
-        let new_offset = (offset + steps) % instructions.len();
-        let next_start = (end, new_offset);
-        if !seen.contains(&next_start) {
-            pile.push(next_start);
-            seen.insert(next_start);
+        let hop = atoz(start, offset, instructions, &left_map, &right_map);
+        short_map.insert((start, offset), hop);
+        if let Some((end, steps)) = hop {
+            let next_start = (end, (offset + steps) % instructions.len());
+            if seen.insert(next_start) {
+                pile.push(next_start);
+            }
         }
     }
-    let mut step = 0;
-    let mut current: Vec<(&str, usize, usize, &str)> = nodes_for_start
+
+    let ghosts: Vec<Ghost> = nodes_for_start
         .iter()
-        .map(|&node| {
-            let (next_node, num_steps) = *short_map.get(&(node, step)).unwrap();
-            (node, step, num_steps, next_node)
+        .map(|&start| {
+            Ghost::from_short_map(start, instructions.len(), &short_map)
+                .ok_or_else(|| format!("the ghost starting at {start} never reaches a Z node."))
         })
-        .collect();
-    loop {
-        let (candidate_nodes, candidate_steps): (Vec<&str>, Vec<usize>) = current
-            .iter()
-            .map(|&(node, _offset, steps, _next)| (node, steps))
-            .unzip();
-        let min_step = *candidate_steps.iter().min().unwrap();
-        step = min_step;
-        if candidate_steps.iter().all_equal()
-            && candidate_nodes.iter().all(|node| node.ends_with('Z'))
-        {
-            break;
+        .collect::<Result<_, _>>()?;
+
+    /*
+    By checking the generated short_map of the real input, all paths from Ak -> Zk have a
+    corresponding Zk -> Zk loop of the same length, and no other Z node is visited.
+    The LCM between the corresponding lengths for each starting node yields the answer.
+
+    When that does not hold, the ghosts meet at the earliest time that is a Z position of
+    every ghost, either before all of them loop or as a solution of their loop congruences.
+     */
+    if ghosts.iter().all(Ghost::is_simple_loop) {
+        return Ok(lcm_all(ghosts.iter().map(|ghost| ghost.cycle_length)));
+    }
+    earliest_meeting(&ghosts).ok_or_else(|| "the ghosts never meet on Z nodes.".to_string())
+}
+
+/// The Z nodes a ghost visits, as found by following the short_map from its start node.
+#[derive(Debug, PartialEq)]
+struct Ghost {
+    /// The time at which the ghost enters its loop.
+    offset: u64,
+    /// 0 if the ghost stops visiting Z nodes from `offset` on.
+    cycle_length: u64,
+    /// The times at which the ghost is on a Z node, until it has completed its loop once.
+    /// Positions from `offset` on repeat every `cycle_length` steps.
+    z_positions: Vec<u64>,
+}
+
+impl Ghost {
+    fn from_short_map(
+        start: &str,
+        instructions_len: usize,
+        short_map: &HashMap<(&str, usize), Option<(&str, usize)>>,
+    ) -> Option<Self> {
+        // (Z node, instruction offset) of every hop, and the time it was reached.
+        let mut first_seen: HashMap<(&str, usize), usize> = HashMap::new();
+        let mut z_positions: Vec<u64> = vec![];
+        let mut state = (start, 0);
+        let mut time = 0;
+
+        loop {
+            let Some((next, steps)) = short_map[&state] else {
+                // the ghost never reaches a Z node again.
+                let last = *z_positions.last()?;
+                return Some(Ghost {
+                    offset: last + 1,
+                    cycle_length: 0,
+                    z_positions,
+                });
+            };
+            time += steps as u64;
+            state = (next, (state.1 + steps) % instructions_len);
+
+            if let Some(&idx) = first_seen.get(&state) {
+                let offset = z_positions[idx];
+                return Some(Ghost {
+                    offset,
+                    cycle_length: time - offset,
+                    z_positions,
+                });
+            }
+            first_seen.insert(state, z_positions.len());
+            z_positions.push(time);
         }
-        current = current
+    }
+
+    /// Whether the ghost is on a Z node exactly at the multiples of its cycle length.
+    fn is_simple_loop(&self) -> bool {
+        self.z_positions == [self.cycle_length]
+    }
+
+    fn is_on_z(&self, time: u64) -> bool {
+        if time < self.offset {
+            self.z_positions.contains(&time)
+        } else {
+            self.loop_positions()
+                .any(|z| (time - z).is_multiple_of(self.cycle_length))
+        }
+    }
+
+    /// All times at which the ghost is on a Z node, in ascending order.
+    fn visits(&self) -> impl Iterator<Item = u64> + '_ {
+        let before_loop = self
+            .z_positions
             .iter()
-            .map(|&(node, offset, steps, next)| {
-                if steps <= step {
-                    let next_offset = (offset + steps) % instructions.len();
-                    let (next_next, next_steps) = *short_map.get(&(next, next_offset)).unwrap();
-                    (next, next_offset, steps + next_steps, next_next)
-                } else {
-                    (node, offset, steps, next)
-                }
-            })
-            .collect();
+            .copied()
+            .filter(|&z| z < self.offset);
+        let repetitions = (0..).take_while(|_| self.cycle_length > 0);
+        let in_loop = repetitions.flat_map(move |repetition| {
+            self.loop_positions()
+                .map(move |z| z + repetition * self.cycle_length)
+        });
+        before_loop.chain(in_loop)
     }
-    /*
-    This code is actually not efficient enough to reach the answer.
-    I feel like there might not be a generic way to efficiently solve this,
-    but by checking the generated short_map we can generate the graph and
-    observe the solution can easily be computed with the Least common multiple.
 
-    All paths from Ak -> Zk have a corresponding Zk -> Zk loop.
-    Both are always the same length.
-    The LCM between the corresponding lengths for each starting node yields the answer.
-     */
-    Some(step as u32)
+    fn loop_positions(&self) -> impl Iterator<Item = u64> + '_ {
+        self.z_positions
+            .iter()
+            .copied()
+            .filter(|&z| z >= self.offset && self.cycle_length > 0)
+    }
+}
+
+fn earliest_meeting(ghosts: &[Ghost]) -> Option<u64> {
+    let first = ghosts.first()?;
+    let all_looping = ghosts.iter().map(|ghost| ghost.offset).max()?;
+
+    // meetings before every ghost loops are visits of the first ghost.
+    let early = first
+        .visits()
+        .take_while(|&time| time < all_looping)
+        .find(|&time| ghosts.iter().all(|ghost| ghost.is_on_z(time)));
+    if early.is_some() {
+        return early;
+    }
+
+    // afterwards, every combination of loop positions gives a system of congruences.
+    ghosts
+        .iter()
+        .map(|ghost| ghost.loop_positions().collect::<Vec<_>>())
+        .multi_cartesian_product()
+        .filter_map(|positions| {
+            let congruences = positions
+                .iter()
+                .zip(ghosts)
+                .map(|(&z, ghost)| (z as i128, ghost.cycle_length as i128));
+            let (time, modulus) = crt(congruences)?;
+            // the smallest solution at which every ghost is in its loop.
+            let earliest = *positions.iter().max()?.max(&all_looping) as i128;
+            let repetitions = (earliest - time + modulus - 1).max(0) / modulus;
+            u64::try_from(time + repetitions * modulus).ok()
+        })
+        .min()
 }
 
 fn atoz<'a>(
@@ -153,9 +244,15 @@ fn atoz<'a>(
     instructions: &'a str,
     left_map: &'a HashMap<&'a str, &'a str>,
     right_map: &'a HashMap<&'a str, &'a str>,
-) -> (&'a str, usize) {
+) -> Option<(&'a str, usize)> {
     let sensible_offset = offset % instructions.len();
-    let instruction_loop = instructions.chars().cycle().skip(sensible_offset);
+    // after visiting every (node, offset) pair without reaching Z, the ghost walks in circles.
+    let max_steps = left_map.len() * instructions.len();
+    let instruction_loop = instructions
+        .chars()
+        .cycle()
+        .skip(sensible_offset)
+        .take(max_steps);
     let loop_until_goal =
         instruction_loop
             .enumerate()
@@ -174,11 +271,10 @@ fn atoz<'a>(
                     false => Continue(return_value),
                 }
             });
-    let result = match loop_until_goal {
+    match loop_until_goal {
         Done((end, count)) => Some((end, count)),
         _ => None,
-    };
-    result.unwrap()
+    }
 }
 
 #[cfg(test)]
@@ -188,7 +284,9 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file_part(
-            "examples", DAY, 1,
+            "examples",
+            DAY,
+            advent_of_code::template::Part::One,
         ));
         assert_eq!(result, Some(2));
     }
@@ -196,8 +294,78 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file_part(
-            "examples", DAY, 2,
+            "examples",
+            DAY,
+            advent_of_code::template::Part::Two,
         ));
-        assert_eq!(result, Some(6));
+        assert_eq!(result, Ok(6));
+    }
+
+    #[test]
+    fn test_part_two_without_lcm_shortcut() {
+        // 11A is on 11Z at 1, 4, 7, ... and 22A on 22Z at 2, 4, 6, ...
+        let input = "L
+
+11A = (11Z, XXX)
+11Z = (11B, XXX)
+11B = (11C, XXX)
+11C = (11Z, XXX)
+22A = (22B, XXX)
+22B = (22Z, XXX)
+22Z = (22B, XXX)
+XXX = (XXX, XXX)";
+        assert_eq!(part_two(input), Ok(4));
+    }
+
+    #[test]
+    fn test_part_two_meeting_before_loops() {
+        // 11A is on 11Z only at 1, 22A on 22Z at 1, 3, 5, ...
+        let input = "L
+
+11A = (11Z, XXX)
+11Z = (11B, XXX)
+11B = (11B, XXX)
+22A = (22Z, XXX)
+22Z = (22C, XXX)
+22C = (22Z, XXX)
+XXX = (XXX, XXX)";
+        assert_eq!(part_two(input), Ok(1));
+
+        // 22A is on 22Z at 2, 4, 6, ...
+        let input = input.replace("22A = (22Z", "22A = (22C");
+        assert!(part_two(&input).is_err());
+    }
+
+    #[test]
+    fn test_part_two_never_meeting() {
+        // 11A is on 11Z at 1, 4, 7, ... and 22A on 22Z at 3, 6, 9, ...
+        let input = "L
+
+11A = (11Z, XXX)
+11Z = (11B, XXX)
+11B = (11C, XXX)
+11C = (11Z, XXX)
+22A = (22B, XXX)
+22B = (22C, XXX)
+22C = (22Z, XXX)
+22Z = (22B, XXX)
+XXX = (XXX, XXX)";
+        assert!(part_two(input).is_err());
+    }
+
+    #[test]
+    fn test_ghost_cycles() {
+        let short_map = HashMap::from([
+            (("11A", 0), Some(("11Z", 2))),
+            (("11Z", 0), Some(("11Z", 2))),
+        ]);
+        assert_eq!(
+            Ghost::from_short_map("11A", 2, &short_map),
+            Some(Ghost {
+                offset: 2,
+                cycle_length: 2,
+                z_positions: vec![2]
+            })
+        );
     }
 }
@@ -1,90 +1,121 @@
 advent_of_code::solution!(9);
 
-use regex::Regex;
-
-pub fn part_one(input: &str) -> Option<i32> {
-    let numbers_re = Regex::new(r"-?\d+").unwrap();
-    let lines = input.trim().split('\n');
-    let number_matches = lines
-        .map(|line| {
-            numbers_re
-                .find_iter(line)
-                .map(|m| m.as_str().parse::<i32>().unwrap())
-                .collect::<Vec<i32>>()
-        })
-        .collect::<Vec<Vec<i32>>>();
-    let results: Vec<i32> = number_matches
-        .iter()
-        .map(|numbers| extrapolate(numbers.iter()))
-        .collect();
-    Some(results.iter().sum::<i32>())
+use advent_of_code::math::Overflow;
+use advent_of_code::parse::{lines, signed};
+
+/*
+A sequence whose differences become constant after d steps is the sequence of values of a
+polynomial of degree d at 0, 1, 2, ... Newton's forward difference formula rebuilds that
+polynomial from the first value of every row of differences:
+
+    p(x) = sum over k of binomial(x, k) * Δ^k p(0)
+
+binomial(x, k) = x (x - 1) ... (x - k + 1) / k! is an integer for every integer x, including
+negative ones, so any position before or after the sequence is evaluated exactly with integers.
+*/
+#[derive(Debug, PartialEq)]
+pub struct Sequence {
+    /// Δ^k p(0) for k = 0, 1, ..., d, the first value of every row of differences down to the
+    /// constant one.
+    leading_differences: Vec<i128>,
+    len: usize,
 }
 
-fn extrapolate(vals: std::slice::Iter<'_, i32>) -> i32 {
-    // Constant extrapolation
-    // vals.fold(None, |_acc, &e| Some(e)).unwrap_or_default()
-
-    // First order extrapolation
-    // vals.fold((0, (0, 0)), |(extrapolation, derivatives), &e| {
-    //     let new_derivatives = (derivatives.0, e + derivatives.1 - extrapolation);
-    //     let new_extrapolation = e + new_derivatives.1;
-    //     (new_extrapolation, new_derivatives)
-    // })
-    // .0
-
-    // Second order extrapolation
-    // vals.fold((0, (0, 0, 0)), |(_extrapolation, derivatives), &e| {
-    //     let new_derivatives = (e, e - derivatives.0, e - derivatives.0 - derivatives.1);
-    //     let new_extrapolation = e + new_derivatives.1;
-    //     (new_extrapolation, new_derivatives)
-    // })
-    // .0
-
-    // Arbitrary order extrapolation
-    vals.fold((0, vec![]), |(_extrapolation, derivatives), &e| {
-        let mut new_derivatives = vec![e; derivatives.len() + 1];
-        let mut acc = 0;
-        derivatives.iter().enumerate().for_each(|(idx, d)| {
-            acc += d;
-            *new_derivatives.get_mut(idx + 1).unwrap() -= acc;
-        });
-        let new_extrapolation = new_derivatives.iter().sum();
-        (new_extrapolation, new_derivatives)
-    })
-    .0
+impl Sequence {
+    /// Fails if the differences do not become constant while at least two of them are left, as
+    /// the values do not determine a polynomial then.
+    pub fn new(values: &[i64]) -> Result<Self, String> {
+        let mut row: Vec<i128> = values.iter().map(|value| *value as i128).collect();
+        let mut leading_differences = vec![];
+
+        while row.len() >= 2 {
+            leading_differences.push(row[0]);
+            if row.iter().all(|value| *value == row[0]) {
+                return Ok(Sequence {
+                    leading_differences,
+                    len: values.len(),
+                });
+            }
+            row = row
+                .windows(2)
+                .map(|pair| pair[1].checked_sub(pair[0]).ok_or(Overflow))
+                .collect::<Result<_, _>>()
+                .map_err(|e| e.to_string())?;
+        }
+
+        Err(format!(
+            "the differences of {} values never become constant",
+            values.len()
+        ))
+    }
+
+    /// The degree of the polynomial, 0 for a constant sequence.
+    pub fn degree(&self) -> usize {
+        self.leading_differences.len() - 1
+    }
+
+    /// The value at `index`, which counts from the first value and may be negative or lie
+    /// beyond the last value.
+    pub fn value_at(&self, index: i64) -> Result<i64, Overflow> {
+        let x = index as i128;
+        let mut value: i128 = 0;
+        let mut binomial: i128 = 1;
+
+        for (k, difference) in self.leading_differences.iter().enumerate() {
+            if k > 0 {
+                // exact, as binomial(x, k - 1) * (x - k + 1) = k * binomial(x, k).
+                binomial = binomial.checked_mul(x - k as i128 + 1).ok_or(Overflow)? / k as i128;
+            }
+            let term = binomial.checked_mul(*difference).ok_or(Overflow)?;
+            value = value.checked_add(term).ok_or(Overflow)?;
+        }
+
+        i64::try_from(value).map_err(|_| Overflow)
+    }
+
+    /// The value `steps` positions after the last one.
+    pub fn after(&self, steps: u64) -> Result<i64, Overflow> {
+        let last = self.len as i64 - 1;
+        self.value_at(last.checked_add_unsigned(steps).ok_or(Overflow)?)
+    }
+
+    /// The value `steps` positions before the first one.
+    pub fn before(&self, steps: u64) -> Result<i64, Overflow> {
+        self.value_at(0i64.checked_sub_unsigned(steps).ok_or(Overflow)?)
+    }
 }
 
-pub fn part_two(input: &str) -> Option<i32> {
-    let numbers_re = Regex::new(r"-?\d+").unwrap();
-    let lines = input.trim().split('\n');
-    let number_matches = lines
-        .map(|line| {
-            numbers_re
-                .find_iter(line)
-                .map(|m| m.as_str().parse::<i32>().unwrap())
-                .collect::<Vec<i32>>()
+fn parse_sequences(input: &str) -> Result<Vec<Sequence>, String> {
+    lines(input)
+        .enumerate()
+        .map(|(idx, line)| {
+            let values: Vec<i64> = signed(line)
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("line {}: {e}", idx + 1))?;
+            Sequence::new(&values).map_err(|e| format!("line {}: {e}", idx + 1))
         })
-        .collect::<Vec<Vec<i32>>>();
-    let results: Vec<i32> = number_matches
+        .collect()
+}
+
+/// The sum of one extrapolated value per sequence.
+fn sum_extrapolated(
+    input: &str,
+    extrapolate: impl Fn(&Sequence) -> Result<i64, Overflow>,
+) -> Result<i64, String> {
+    parse_sequences(input)?
         .iter()
-        .map(|numbers| extrapolate_backwards(numbers.iter()))
-        .collect();
-    Some(results.iter().sum::<i32>())
+        .try_fold(0i64, |sum, sequence| {
+            sum.checked_add(extrapolate(sequence)?).ok_or(Overflow)
+        })
+        .map_err(|e| e.to_string())
+}
+
+pub fn part_one(input: &str) -> Result<i64, String> {
+    sum_extrapolated(input, |sequence| sequence.after(1))
 }
 
-fn extrapolate_backwards(vals: std::slice::Iter<'_, i32>) -> i32 {
-    vals.fold((0, vec![]), |(extrapolation, derivatives), &e| {
-        let mut new_derivatives = vec![e; derivatives.len() + 1];
-        let mut acc = 0;
-        derivatives.iter().enumerate().for_each(|(idx, d)| {
-            acc += d;
-            *new_derivatives.get_mut(idx + 1).unwrap() -= acc;
-        });
-        let new_extrapolation = extrapolation
-            + new_derivatives.last().unwrap() * (-1i32).pow(new_derivatives.len() as u32 + 1);
-        (new_extrapolation, new_derivatives)
-    })
-    .0
+pub fn part_two(input: &str) -> Result<i64, String> {
+    sum_extrapolated(input, |sequence| sequence.before(1))
 }
 
 #[cfg(test)]
@@ -94,12 +125,73 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(114));
+        assert_eq!(result, Ok(114));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(2));
+        assert_eq!(result, Ok(2));
+    }
+
+    #[test]
+    fn test_several_steps() {
+        let sequences =
+            parse_sequences(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let degrees: Vec<usize> = sequences.iter().map(Sequence::degree).collect();
+        assert_eq!(degrees, [1, 2, 3]);
+
+        assert_eq!(sequences[0].after(3), Ok(24));
+        assert_eq!(sequences[0].before(2), Ok(-6));
+        assert_eq!(sequences[1].after(2), Ok(36));
+        assert_eq!(sequences[1].before(3), Ok(1));
+        assert_eq!(sequences[2].after(1), Ok(68));
+        assert_eq!(sequences[2].before(1), Ok(5));
+        assert_eq!(sequences[2].value_at(3), Ok(21));
+    }
+
+    #[test]
+    fn test_matches_polynomial() {
+        let p = |x: i64| 3 * x.pow(4) - 2 * x.pow(3) + 7 * x - 11;
+        let values: Vec<i64> = (-3..5).map(p).collect();
+        let sequence = Sequence::new(&values).unwrap();
+        assert_eq!(sequence.degree(), 4);
+        for x in -40..40 {
+            assert_eq!(sequence.value_at(x), Ok(p(x - 3)), "x = {x}");
+        }
+    }
+
+    #[test]
+    fn test_reports_errors() {
+        assert_eq!(
+            part_one("0 1 2\n1 2 4 8 16\n"),
+            Err("line 2: the differences of 5 values never become constant".to_string())
+        );
+        assert!(Sequence::new(&[5]).is_err());
+        assert_eq!(Sequence::new(&[5, 5]).unwrap().after(100), Ok(5));
+
+        let steep = Sequence::new(&[-(1 << 62), 0, 1 << 62]).unwrap();
+        assert_eq!(steep.after(0), Ok(1 << 62));
+        assert_eq!(steep.after(1), Err(Overflow));
+        assert_eq!(steep.before(2), Err(Overflow));
+        assert_eq!(steep.after(u64::MAX), Err(Overflow));
+        assert_eq!(
+            part_two("9223372036854775807 9223372036854775807\n1 1\n"),
+            Err("arithmetic overflow".to_string())
+        );
+
+        assert_eq!(
+            part_one("1 2 3\n-9223372036854775809 0\n"),
+            Err(
+                "line 2: at column 1: integer `-9223372036854775809` does not fit into i64."
+                    .to_string()
+            )
+        );
+
+        // the differences themselves no longer fit into an i64.
+        assert_eq!(
+            Sequence::new(&[i64::MAX, i64::MIN, i64::MAX]),
+            Err("the differences of 3 values never become constant".to_string())
+        );
     }
 }
@@ -1,8 +1,14 @@
 use std::collections::HashSet;
 
+use advent_of_code::template::{ANSI_BOLD, ANSI_RESET};
 use itertools::Itertools;
 
-advent_of_code::solution!(10);
+advent_of_code::solution!(
+    10,
+    params {
+        render: bool = false
+    }
+);
 
 pub fn part_one(input: &str) -> Option<u32> {
     let lines = input.trim().split('\n');
@@ -25,8 +31,8 @@ pub fn part_one(input: &str) -> Option<u32> {
     Some(result)
 }
 
-fn navigate(tiles: &Vec<Vec<char>>, start: (usize, usize)) -> u32 {
-    let (height, width) = (tiles.len(), tiles.get(0).unwrap().len());
+fn navigate(tiles: &[Vec<char>], start: (usize, usize)) -> u32 {
+    let (height, width) = (tiles.len(), tiles.first().unwrap().len());
     let mut seen: HashSet<(usize, usize)> = HashSet::new();
     seen.insert(start);
 
@@ -94,7 +100,7 @@ fn expand(tile: &char, position: (usize, usize)) -> Vec<(usize, usize)> {
     .collect()
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(input: &str, params: &Params) -> Option<u32> {
     let lines = input.trim().split('\n');
     let mut tiles: Vec<Vec<char>> = lines.map(|line| line.chars().collect()).collect();
     let start = tiles
@@ -114,9 +120,87 @@ pub fn part_two(input: &str) -> Option<u32> {
     let start_tile_content = infer_start_tile(&tiles, start);
     let start_cell = tiles.get_mut(start.0).unwrap().get_mut(start.1).unwrap();
     *start_cell = start_tile_content;
-    let loop_nodes = identify_loop(&tiles, start);
-    let area = count_area(&tiles, &loop_nodes);
-    Some(area)
+    let loop_tiles = trace_loop(&tiles, start);
+    if params.render {
+        let loop_nodes: HashSet<(usize, usize)> = loop_tiles.iter().copied().collect();
+        let enclosed = enclosed_tiles(&tiles, &loop_nodes);
+        eprintln!("{}", render(&tiles, &loop_nodes, &enclosed));
+        // the render needs the scanned tiles anyway, their count spares a second computation.
+        return Some(enclosed.len() as u32);
+    }
+    Some(enclosed_area(&loop_tiles))
+}
+
+/// The tiles of the loop in the order they are connected, beginning at `start`, whose tile has
+/// to be inferred already.
+fn trace_loop(tiles: &[Vec<char>], start: (usize, usize)) -> Vec<(usize, usize)> {
+    let mut loop_tiles = vec![start];
+    let mut previous = start;
+    let mut current = expand(&tiles[start.0][start.1], start)[0];
+    while current != start {
+        loop_tiles.push(current);
+        let next = expand(&tiles[current.0][current.1], current)
+            .into_iter()
+            .find(|&neighbor| neighbor != previous)
+            .unwrap();
+        previous = current;
+        current = next;
+    }
+    loop_tiles
+}
+
+/*
+The centres of the loop tiles, in order, are the vertices of a polygon. The shoelace formula
+gives its area A, and Pick's theorem A = i + b/2 - 1 relates it to the b grid points on its
+boundary, the loop tiles, and the i grid points inside, the enclosed tiles:
+
+    i = A - b/2 + 1
+*/
+fn enclosed_area(loop_tiles: &[(usize, usize)]) -> u32 {
+    let twice_area: i64 = loop_tiles
+        .iter()
+        .zip(loop_tiles.iter().cycle().skip(1))
+        .map(|(&(row, col), &(next_row, next_col))| {
+            col as i64 * next_row as i64 - next_col as i64 * row as i64
+        })
+        .sum();
+    ((twice_area.abs() - loop_tiles.len() as i64 + 2) / 2) as u32
+}
+
+/// The maze with the loop drawn in box-drawing characters and the enclosed tiles in bold.
+fn render(
+    tiles: &[Vec<char>],
+    loop_nodes: &HashSet<(usize, usize)>,
+    enclosed: &HashSet<(usize, usize)>,
+) -> String {
+    tiles
+        .iter()
+        .enumerate()
+        .map(|(row_idx, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(col_idx, cell)| {
+                    let location = (row_idx, col_idx);
+                    if loop_nodes.contains(&location) {
+                        match cell {
+                            '|' => "│",
+                            '-' => "─",
+                            'L' => "└",
+                            'J' => "┘",
+                            '7' => "┐",
+                            'F' => "┌",
+                            _ => "?",
+                        }
+                        .to_string()
+                    } else if enclosed.contains(&location) {
+                        format!("{ANSI_BOLD}•{ANSI_RESET}")
+                    } else {
+                        " ".to_string()
+                    }
+                })
+                .collect::<String>()
+        })
+        .join("\n")
 }
 
 fn infer_start_tile(tiles: &[Vec<char>], start: (usize, usize)) -> char {
@@ -154,16 +238,21 @@ enum BoundaryStatus {
     ExitFromBelow,
 }
 
-fn count_area(tiles: &[Vec<char>], loop_nodes: &HashSet<(usize, usize)>) -> u32 {
+/// The tiles inside the loop, found by scanning every row from the left and tracking whether the
+/// loop was crossed an odd number of times.
+fn enclosed_tiles(
+    tiles: &[Vec<char>],
+    loop_nodes: &HashSet<(usize, usize)>,
+) -> HashSet<(usize, usize)> {
     tiles
         .iter()
         .enumerate()
-        .map(|(row_idx, row)| {
+        .flat_map(|(row_idx, row)| {
             row.iter()
                 .enumerate()
                 .fold(
-                    (0, BoundaryStatus::Outside),
-                    |(area, status), (col_idx, cell)| {
+                    (vec![], BoundaryStatus::Outside),
+                    |(mut enclosed, status), (col_idx, cell)| {
                         let location = (row_idx, col_idx);
                         if loop_nodes.contains(&location) {
                             let new_status = match cell {
@@ -203,57 +292,18 @@ fn count_area(tiles: &[Vec<char>], loop_nodes: &HashSet<(usize, usize)>) -> u32
                                 .unwrap(),
                                 _ => status,
                             };
-                            (area, new_status)
+                            (enclosed, new_status)
                         } else {
-                            match status {
-                                BoundaryStatus::Inside => (area + 1, status),
-                                _ => (area, status),
+                            if let BoundaryStatus::Inside = status {
+                                enclosed.push(location);
                             }
+                            (enclosed, status)
                         }
                     },
                 )
                 .0
         })
-        .sum()
-}
-
-fn identify_loop(tiles: &Vec<Vec<char>>, start: (usize, usize)) -> HashSet<(usize, usize)> {
-    let (height, width) = (tiles.len(), tiles.get(0).unwrap().len());
-    let mut seen: HashSet<(usize, usize)> = HashSet::new();
-    seen.insert(start);
-
-    let mut current_nodes: Vec<(u32, (usize, usize))>;
-    let mut next_nodes = vec![(0, start)];
-    while !next_nodes.is_empty() {
-        current_nodes = next_nodes;
-        next_nodes = current_nodes
-            .iter()
-            .flat_map(|&(steps, node)| {
-                let (row_index, col_index) = node;
-                let tile = tiles.get(row_index).unwrap().get(col_index).unwrap();
-                let neighbor_candidates = expand(tile, node);
-                let validated_neighbors: Vec<(u32, (usize, usize))> = neighbor_candidates
-                    .iter()
-                    .filter(|candidate| !seen.contains(candidate))
-                    .filter(|(row_index, col_index)| {
-                        (0..height).contains(row_index) && (0..width).contains(col_index)
-                    })
-                    .map(|&(row_index, col_index)| {
-                        // Valid position
-                        let tile = tiles.get(row_index).unwrap().get(col_index).unwrap();
-                        (tile, (row_index, col_index))
-                    })
-                    .filter(|&(tile, candidate)| expand(tile, candidate).contains(&node))
-                    .map(|(_tile, candidate)| (steps + 1, candidate))
-                    .collect();
-                validated_neighbors
-            })
-            .collect();
-        next_nodes.iter().for_each(|&(_, node)| {
-            seen.insert(node);
-        });
-    }
-    seen
+        .collect()
 }
 
 #[cfg(test)]
@@ -263,16 +313,139 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file_part(
-            "examples", DAY, 1,
+            "examples",
+            DAY,
+            advent_of_code::template::Part::One,
         ));
         assert_eq!(result, Some(8));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file_part(
-            "examples", DAY, 2,
-        ));
+        let result = part_two(
+            &advent_of_code::template::read_file_part(
+                "examples",
+                DAY,
+                advent_of_code::template::Part::Two,
+            ),
+            &Params::default(),
+        );
         assert_eq!(result, Some(10));
     }
+
+    #[test]
+    fn test_part_two_rendered() {
+        let input = advent_of_code::template::read_file_part(
+            "examples",
+            DAY,
+            advent_of_code::template::Part::Two,
+        );
+        assert_eq!(part_two(&input, &Params { render: true }), Some(10));
+        assert_eq!(part_two(SQUARE, &Params { render: true }), Some(4));
+    }
+
+    const SQUARE: &str = "...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........
+";
+
+    const SQUEEZED: &str = "..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........
+";
+
+    const LARGER: &str = ".F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...
+";
+
+    /// The tiles with the start tile inferred, the ordered loop and the scanned enclosed tiles.
+    type Analysis = (Vec<Vec<char>>, Vec<(usize, usize)>, HashSet<(usize, usize)>);
+
+    fn analyze(input: &str) -> Analysis {
+        let mut tiles: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+        let start = (0..tiles.len())
+            .flat_map(|row| (0..tiles[row].len()).map(move |col| (row, col)))
+            .find(|&(row, col)| tiles[row][col] == 'S')
+            .unwrap();
+        tiles[start.0][start.1] = infer_start_tile(&tiles, start);
+        let loop_tiles = trace_loop(&tiles, start);
+        let loop_nodes = loop_tiles.iter().copied().collect();
+        let enclosed = enclosed_tiles(&tiles, &loop_nodes);
+        (tiles, loop_tiles, enclosed)
+    }
+
+    #[test]
+    fn test_pick_matches_scanline() {
+        let examples = [
+            (
+                advent_of_code::template::read_file_part(
+                    "examples",
+                    DAY,
+                    advent_of_code::template::Part::One,
+                ),
+                1,
+            ),
+            (
+                advent_of_code::template::read_file_part(
+                    "examples",
+                    DAY,
+                    advent_of_code::template::Part::Two,
+                ),
+                10,
+            ),
+            (SQUARE.to_string(), 4),
+            (SQUEEZED.to_string(), 4),
+            (LARGER.to_string(), 8),
+        ];
+        for (input, expected) in examples {
+            let (_, loop_tiles, enclosed) = analyze(&input);
+            assert_eq!(enclosed.len(), expected, "scanline of\n{input}");
+            assert_eq!(
+                enclosed_area(&loop_tiles) as usize,
+                expected,
+                "pick of\n{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_trace_loop() {
+        let (_, loop_tiles, _) = analyze(SQUARE);
+        assert_eq!(loop_tiles.len(), 46);
+        assert_eq!(loop_tiles[0], (1, 1));
+        // consecutive tiles, including the last and the first one, are adjacent.
+        for (a, b) in loop_tiles.iter().zip(loop_tiles.iter().cycle().skip(1)) {
+            assert_eq!(a.0.abs_diff(b.0) + a.1.abs_diff(b.1), 1);
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let (tiles, loop_tiles, enclosed) = analyze(".....\n.S-7.\n.|.|.\n.L-J.\n.....\n");
+        let loop_nodes = loop_tiles.iter().copied().collect();
+        assert_eq!(
+            render(&tiles, &loop_nodes, &enclosed),
+            format!("     \n ┌─┐ \n │{ANSI_BOLD}•{ANSI_RESET}│ \n └─┘ \n     ")
+        );
+    }
 }
@@ -1,114 +1,142 @@
-use itertools::Itertools;
-use std::collections::HashSet;
-
-advent_of_code::solution!(11);
+advent_of_code::solution!(
+    11,
+    params {
+        factor: u64 = 1_000_000
+    }
+);
 
 pub fn part_one(input: &str) -> Option<u64> {
-    let galaxy_locations: Vec<(usize, usize)> = input
-        .trim()
-        .split('\n')
-        .enumerate()
-        .flat_map(move |(row_idx, row)| {
-            row.chars()
-                .enumerate()
-                .filter_map(move |(col_idx, cell)| match cell {
-                    '#' => Some((row_idx, col_idx)),
-                    '.' => None,
-                    _ => unimplemented!(),
-                })
-        })
-        .collect();
-    let rows_with_galaxies = galaxy_locations
-        .iter()
-        .map(|&(row_idx, _col_idx)| row_idx)
-        .collect::<HashSet<usize>>();
-    let cols_with_galaxies = galaxy_locations
-        .iter()
-        .map(|&(_row_idx, col_idx)| col_idx)
-        .collect::<HashSet<usize>>();
-    let sum_distances: u64 = galaxy_locations
-        .iter()
-        .combinations(2)
-        .map(|pair| {
-            space_dilated_distance(
-                pair.get(0).unwrap(),
-                pair.get(1).unwrap(),
-                &rows_with_galaxies,
-                &cols_with_galaxies,
-                2,
-            )
-        })
-        .sum();
-    Some(sum_distances)
+    sum_distances(input, 2)
 }
 
-fn space_dilated_distance(
-    first: &(usize, usize),
-    second: &(usize, usize),
-    rows_with_galaxies: &HashSet<usize>,
-    cols_with_galaxies: &HashSet<usize>,
-    dilation_factor: u64,
-) -> u64 {
-    let &(first_row, first_col) = first;
-    let &(second_row, second_col) = second;
-    let abs_diff = first_row.abs_diff(second_row) + first_col.abs_diff(second_col);
-    let dilated_rows = (first_row..second_row)
-        .filter(|row| !rows_with_galaxies.contains(row))
-        .count()
-        + (second_row..first_row)
-            .filter(|row| !rows_with_galaxies.contains(row))
-            .count();
-    let dilated_cols = (first_col..second_col)
-        .filter(|col| !cols_with_galaxies.contains(col))
-        .count()
-        + (second_col..first_col)
-            .filter(|col| !cols_with_galaxies.contains(col))
-            .count();
-    abs_diff as u64 + (dilated_rows + dilated_cols) as u64 * (dilation_factor - 1)
+/// The row and column of every galaxy, `None` if the image holds anything but `#` and `.`.
+fn galaxy_locations(input: &str) -> Option<Vec<(usize, usize)>> {
+    let mut locations = vec![];
+    for (row_idx, row) in input.trim().split('\n').enumerate() {
+        for (col_idx, cell) in row.chars().enumerate() {
+            match cell {
+                '#' => locations.push((row_idx, col_idx)),
+                '.' => {}
+                _ => return None,
+            }
+        }
+    }
+    Some(locations)
+}
+
+/*
+The distance between two galaxies is the sum of their distances along each axis, so the sum over
+all pairs splits into one sum per axis. Along an axis, the sorted coordinates are first moved to
+their dilated positions p_0 <= p_1 <= ... Each p_i is then p_i - p_j away from every earlier p_j,
+which adds up to i * p_i minus the sum of the earlier positions.
+*/
+fn sum_distances(input: &str, dilation_factor: u64) -> Option<u64> {
+    let galaxy_locations = galaxy_locations(input)?;
+    let rows = galaxy_locations.iter().map(|&(row_idx, _col_idx)| row_idx);
+    let cols = galaxy_locations.iter().map(|&(_row_idx, col_idx)| col_idx);
+    Some(
+        sum_axis_distances(rows.collect(), dilation_factor)
+            + sum_axis_distances(cols.collect(), dilation_factor),
+    )
+}
+
+/// The sum of the distances between all pairs of `coordinates` along one axis, where every
+/// coordinate without a galaxy in between counts `dilation_factor` times.
+fn sum_axis_distances(mut coordinates: Vec<usize>, dilation_factor: u64) -> u64 {
+    coordinates.sort_unstable();
+
+    let mut position = 0;
+    let mut sum_previous_positions = 0;
+    let mut sum_distances = 0;
+    for (idx, pair) in coordinates.windows(2).enumerate() {
+        let gap = (pair[1] - pair[0]) as u64;
+        if gap > 0 {
+            position += 1 + (gap - 1) * dilation_factor;
+        }
+        // the first coordinate is at position 0, so that it adds nothing to the sum.
+        let earlier = idx as u64 + 1;
+        sum_distances += earlier * position - sum_previous_positions;
+        sum_previous_positions += position;
+    }
+    sum_distances
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let galaxy_locations: Vec<(usize, usize)> = input
-        .trim()
-        .split('\n')
-        .enumerate()
-        .flat_map(move |(row_idx, row)| {
-            row.chars()
-                .enumerate()
-                .filter_map(move |(col_idx, cell)| match cell {
-                    '#' => Some((row_idx, col_idx)),
-                    '.' => None,
-                    _ => unimplemented!(),
-                })
-        })
-        .collect();
-    let rows_with_galaxies = galaxy_locations
-        .iter()
-        .map(|&(row_idx, _col_idx)| row_idx)
-        .collect::<HashSet<usize>>();
-    let cols_with_galaxies = galaxy_locations
-        .iter()
-        .map(|&(_row_idx, col_idx)| col_idx)
-        .collect::<HashSet<usize>>();
-    let sum_distances: u64 = galaxy_locations
-        .iter()
-        .combinations(2)
-        .map(|pair| {
-            space_dilated_distance(
-                pair.get(0).unwrap(),
-                pair.get(1).unwrap(),
-                &rows_with_galaxies,
-                &cols_with_galaxies,
-                1000000,
-            )
-        })
-        .sum();
-    Some(sum_distances)
+pub fn part_two(input: &str, params: &Params) -> Option<u64> {
+    sum_distances(input, params.factor)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::rng::Rng;
+    use itertools::Itertools;
+    use std::collections::HashSet;
+
+    /// The sum over all pairs of galaxies, recounting the rows and columns between each pair.
+    fn sum_distances_pairwise(input: &str, dilation_factor: u64) -> Option<u64> {
+        let galaxy_locations = galaxy_locations(input)?;
+        let rows_with_galaxies = galaxy_locations
+            .iter()
+            .map(|&(row_idx, _col_idx)| row_idx)
+            .collect::<HashSet<usize>>();
+        let cols_with_galaxies = galaxy_locations
+            .iter()
+            .map(|&(_row_idx, col_idx)| col_idx)
+            .collect::<HashSet<usize>>();
+        let sum_distances: u64 = galaxy_locations
+            .iter()
+            .combinations(2)
+            .map(|pair| {
+                space_dilated_distance(
+                    pair.first().unwrap(),
+                    pair.get(1).unwrap(),
+                    &rows_with_galaxies,
+                    &cols_with_galaxies,
+                    dilation_factor,
+                )
+            })
+            .sum();
+        Some(sum_distances)
+    }
+
+    fn space_dilated_distance(
+        first: &(usize, usize),
+        second: &(usize, usize),
+        rows_with_galaxies: &HashSet<usize>,
+        cols_with_galaxies: &HashSet<usize>,
+        dilation_factor: u64,
+    ) -> u64 {
+        let &(first_row, first_col) = first;
+        let &(second_row, second_col) = second;
+        let abs_diff = first_row.abs_diff(second_row) + first_col.abs_diff(second_col);
+        let dilated_rows = (first_row..second_row)
+            .filter(|row| !rows_with_galaxies.contains(row))
+            .count()
+            + (second_row..first_row)
+                .filter(|row| !rows_with_galaxies.contains(row))
+                .count();
+        let dilated_cols = (first_col..second_col)
+            .filter(|col| !cols_with_galaxies.contains(col))
+            .count()
+            + (second_col..first_col)
+                .filter(|col| !cols_with_galaxies.contains(col))
+                .count();
+        let dilated = dilated_rows + dilated_cols;
+        (abs_diff - dilated) as u64 + dilated as u64 * dilation_factor
+    }
+
+    /// A map of up to 20 by 20 tiles, from empty to crowded.
+    fn random_galaxy_map(rng: &mut Rng) -> String {
+        let (width, height) = (1 + rng.below(20), 1 + rng.below(20));
+        let density = 1 + rng.below(50);
+        (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| if rng.below(100) < density { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .join("\n")
+    }
 
     #[test]
     fn test_part_one() {
@@ -118,7 +146,49 @@ mod tests {
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_two(&input, &Params::default());
         assert_eq!(result, Some(82000210));
+        let result = part_two(&input, &Params { factor: 10 });
+        assert_eq!(result, Some(1030));
+        let result = part_two(&input, &Params { factor: 100 });
+        assert_eq!(result, Some(8410));
+    }
+
+    #[test]
+    fn test_matches_pairwise_distances() {
+        let mut rng = Rng::new(0x2545f4914f6cdd1d);
+        for _ in 0..100 {
+            let map = random_galaxy_map(&mut rng);
+            for factor in [0, 1, 2, 10, 1_000_000] {
+                assert_eq!(
+                    sum_distances(&map, factor),
+                    sum_distances_pairwise(&map, factor),
+                    "factor {factor} for\n{map}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_shared_rows_and_columns() {
+        // galaxies in the same row or column are dilated apart along one axis only.
+        assert_eq!(sum_distances("#.#\n...\n#..", 2), Some(3 + 3 + 6));
+        assert_eq!(sum_axis_distances(vec![0, 0, 0], 5), 0);
+        assert_eq!(sum_axis_distances(vec![3, 0, 1], 10), 1 + 12 + 11);
+        assert_eq!(sum_axis_distances(vec![], 10), 0);
+    }
+
+    #[test]
+    fn test_collapsed_space() {
+        // with a factor of 0, empty rows and columns vanish.
+        assert_eq!(sum_distances("#..\n...\n..#", 0), Some(2));
+        assert_eq!(sum_distances_pairwise("#..\n...\n..#", 0), Some(2));
+    }
+
+    #[test]
+    fn test_invalid_tile() {
+        assert_eq!(part_one("#.\n.x"), None);
+        assert_eq!(part_two("#.\n.x", &Params::default()), None);
     }
 }
@@ -1,158 +1,79 @@
 advent_of_code::solution!(12);
 
-use itertools::Itertools;
-use regex::Regex;
+use advent_of_code::memo::Memo;
 
 struct ProblemInput {
     condition_records: String,
     failure_groups: Vec<usize>,
 }
 
-fn parse_line(condition_records_regex: &Regex, numbers_regex: &Regex, line: &str) -> ProblemInput {
-    let failure_groups = numbers_regex
-        .find_iter(line)
-        .map(|m| m.as_str().parse::<usize>().unwrap())
-        .collect();
-    let condition_records = condition_records_regex.find(line).unwrap().as_str();
+fn parse_line(line: &str) -> ProblemInput {
+    let (condition_records, failure_groups) = line.split_once(' ').unwrap();
     ProblemInput {
         condition_records: condition_records.to_string(),
-        failure_groups,
+        failure_groups: failure_groups
+            .split(',')
+            .map(|n| n.parse::<usize>().unwrap())
+            .collect(),
     }
 }
 
-fn plausible_starts(condition_records: &str, failure_group: usize) -> Vec<usize> {
-    (0..condition_records.len())
-        .map(|start| (start, start + failure_group))
-        .filter(|&(_start, end)| end <= condition_records.len())
-        .filter(|&(_start, end)| {
-            end == condition_records.len() || !condition_records[end..].starts_with('#')
-        })
-        .filter(|&(start, _end)| start == 0 || !condition_records[..start].ends_with('#'))
-        .filter(|&(start, end)| {
-            condition_records[start..end]
-                .chars()
-                .all(|c| c == '#' || c == '?')
-        })
-        .map(|(start, _end)| start)
-        .collect()
-}
+/*
+The arrangements only depend on the position in the records and on the number of
+failure groups that were already placed, so the counts are cached per (position, group).
 
-fn count_arrangements(problem_input: &ProblemInput, visible_failures_regex: &Regex) -> u32 {
-    let mut plausible_failure_group_arrangements: Vec<Vec<usize>> = problem_input
-        .failure_groups
-        .iter()
-        .map(|&failure_group| plausible_starts(&problem_input.condition_records, failure_group))
-        .collect();
-    let visible_failure_groups: Vec<(usize, usize)> = visible_failures_regex
-        .find_iter(&problem_input.condition_records)
-        .map(|m| (m.start(), m.len()))
-        .collect();
-    if !plausible_failure_group_arrangements.is_empty() {
-        if !visible_failure_groups.is_empty() {
-            // Restrict based on first visible failure
-            let (first_start, _first_len) = visible_failure_groups.first().unwrap();
-            let first_failure_group_plausible_starts =
-                plausible_failure_group_arrangements.first_mut().unwrap();
-            // Starts later than the first visible failure are not plausible
-            first_failure_group_plausible_starts.retain(|s| s <= first_start);
-            // Restrict based on last visible failure
-            let (last_start, last_len) = visible_failure_groups.last().unwrap();
-            let last_index = last_start + last_len - 1;
-            let last_failure_group_plausible_starts =
-                plausible_failure_group_arrangements.last_mut().unwrap();
-            // Starts earlier than would include the last visible failure are not plausible
-            let last_failure_group = problem_input.failure_groups.last().unwrap();
-            last_failure_group_plausible_starts.retain(|s| s + last_failure_group >= last_index);
-        }
-        // Restrict based on failure group arrangements ordering
-        // Each group cannot be earlier than the previous group could start and finish
-        (0..plausible_failure_group_arrangements.len())
-            .tuple_windows::<(_, _)>()
-            .for_each(|(prev_idx, next_idx)| {
-                let previous_len = problem_input.failure_groups[prev_idx];
-                let previous_min_start = *plausible_failure_group_arrangements[prev_idx]
-                    .iter()
-                    .min()
-                    .unwrap();
-                plausible_failure_group_arrangements[next_idx]
-                    .retain(|&s| s > previous_min_start + previous_len);
-            });
-        // Restrict based on failure group arrangements ordering
-        // Each group cannot be later than to finish before the next group starts
-        (0..plausible_failure_group_arrangements.len())
-            .rev()
-            .tuple_windows::<(_, _)>()
-            .for_each(|(next_idx, prev_idx)| {
-                let previous_len = problem_input.failure_groups[prev_idx];
-                let next_max_start = *plausible_failure_group_arrangements[next_idx]
-                    .iter()
-                    .max()
-                    .unwrap();
-                plausible_failure_group_arrangements[prev_idx]
-                    .retain(|&s| s + previous_len < next_max_start);
-            });
-    }
-    if plausible_failure_group_arrangements.len() <= 1 {
-        plausible_failure_group_arrangements
-            .first()
-            .unwrap_or(&vec![])
-            .len() as u32
-    } else {
-        validate_combinations(
-            &problem_input.condition_records,
-            &problem_input.failure_groups,
-            &plausible_failure_group_arrangements,
-            0,
-        )
-    }
+At each position, either:
+- the spring is operational ('.' or '?'), and the arrangements continue at the next position.
+- the next failure group starts here ('#' or '?'). It needs as many springs that can be
+  damaged, followed by the end of the records or a spring that can be operational.
+*/
+fn count_arrangements(problem_input: &ProblemInput) -> u64 {
+    let mut memo = Memo::new();
+    count_from(
+        problem_input.condition_records.as_bytes(),
+        &problem_input.failure_groups,
+        (0, 0),
+        &mut memo,
+    )
 }
 
-fn validate_combinations(
-    condition_records: &str,
+fn count_from(
+    records: &[u8],
     failure_groups: &[usize],
-    plausible_failure_group_arrangements: &[Vec<usize>],
-    offset: usize,
-) -> u32 {
-    if let (
-        Some((first_plausible_failure_group_arrangement, rest_plausible_failure_group_arrangement)),
-        Some((first_failure_group, rest_failure_groups)),
-    ) = (
-        plausible_failure_group_arrangements.split_first(),
-        failure_groups.split_first(),
-    ) {
-        let min_start = match offset {
-            0 => 0,
-            value => value + 1,
-        };
-        let max_start = offset + condition_records.find('#').unwrap_or(usize::MAX - offset);
-        first_plausible_failure_group_arrangement
-            .iter()
-            .filter(|&&start| start >= min_start)
-            .filter(|&&start| start <= max_start)
-            .map(|&start| {
-                let end = start + first_failure_group;
-                validate_combinations(
-                    &condition_records[end - offset..],
-                    rest_failure_groups,
-                    rest_plausible_failure_group_arrangement,
-                    end,
-                )
-            })
-            .sum()
-    } else {
-        1
+    (position, group): (usize, usize),
+    memo: &mut Memo<(usize, usize), u64>,
+) -> u64 {
+    if position >= records.len() {
+        return u64::from(group == failure_groups.len());
     }
+
+    memo.get_or_insert_with((position, group), |memo| {
+        let mut count = 0;
+
+        if records[position] != b'#' {
+            count += count_from(records, failure_groups, (position + 1, group), memo);
+        }
+
+        if records[position] != b'.' && group < failure_groups.len() {
+            let end = position + failure_groups[group];
+            let fits = end <= records.len()
+                && !records[position..end].contains(&b'.')
+                && records.get(end) != Some(&b'#');
+            if fits {
+                // the spring after the group is operational.
+                count += count_from(records, failure_groups, (end + 1, group + 1), memo);
+            }
+        }
+
+        count
+    })
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let numbers_regex = Regex::new(r"\d+").unwrap();
-    let condition_records_regex = Regex::new(r"[\.#\?]+").unwrap();
-    let visible_failures_regex = Regex::new(r"#+").unwrap();
-    let parse = |line| parse_line(&condition_records_regex, &numbers_regex, line);
-    let inputs: Vec<ProblemInput> = input.trim().split('\n').map(parse).collect();
-    let result = inputs
-        .iter()
-        .map(|problem_input| count_arrangements(problem_input, &visible_failures_regex))
+pub fn part_one(input: &str) -> Option<u64> {
+    let result = input
+        .lines()
+        .map(parse_line)
+        .map(|problem_input| count_arrangements(&problem_input))
         .sum();
     Some(result)
 }
@@ -170,24 +91,16 @@ fn unfold(problem_input: ProblemInput) -> (String, Vec<usize>) {
     (unfolded_condition_records, unfolded_failure_groups)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let numbers_regex = Regex::new(r"\d+").unwrap();
-    let condition_records_regex = Regex::new(r"[\.#\?]+").unwrap();
-    let visible_failures_regex = Regex::new(r"#+").unwrap();
-    let parse = |line| parse_line(&condition_records_regex, &numbers_regex, line);
-    let inputs: Vec<ProblemInput> = input
-        .trim()
-        .split('\n')
-        .map(parse)
+pub fn part_two(input: &str) -> Option<u64> {
+    let result = input
+        .lines()
+        .map(parse_line)
         .map(unfold)
         .map(|(condition_records, failure_groups)| ProblemInput {
-            condition_records: condition_records.to_string(),
+            condition_records,
             failure_groups,
         })
-        .collect();
-    let result = inputs
-        .iter()
-        .map(|problem_input| count_arrangements(problem_input, &visible_failures_regex))
+        .map(|problem_input| count_arrangements(&problem_input))
         .sum();
     Some(result)
 }
@@ -207,4 +120,38 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(525152));
     }
+
+    #[test]
+    fn test_part_one_per_line() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let counts: Vec<u64> = input
+            .lines()
+            .map(|line| count_arrangements(&parse_line(line)))
+            .collect();
+        assert_eq!(counts, vec![1, 4, 1, 1, 4, 10]);
+    }
+
+    #[test]
+    fn test_part_two_per_line() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let counts: Vec<Option<u64>> = input.lines().map(part_two).collect();
+        assert_eq!(
+            counts,
+            vec![
+                Some(1),
+                Some(16384),
+                Some(1),
+                Some(16),
+                Some(2500),
+                Some(506250)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unfold() {
+        let (records, groups) = unfold(parse_line(".# 1"));
+        assert_eq!(records, ".#?.#?.#?.#?.#");
+        assert_eq!(groups, vec![1; 5]);
+    }
 }
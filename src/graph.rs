@@ -0,0 +1,550 @@
+//! Graph searches over implicit graphs, where the edges of a node are produced by a `successors` closure,
+//! and cycle detection for iterated functions.
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Assigns indices to the nodes discovered by a search and remembers how each one was reached.
+struct Discovered<N> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    parents: Vec<Option<usize>>,
+}
+
+impl<N: Clone + Eq + Hash> Discovered<N> {
+    fn new(start: N) -> Self {
+        Self {
+            nodes: vec![start.clone()],
+            indices: HashMap::from([(start, 0)]),
+            parents: vec![None],
+        }
+    }
+
+    /// The index of `node`, and whether it was seen for the first time.
+    fn insert(&mut self, node: N, parent: usize) -> (usize, bool) {
+        match self.indices.entry(node) {
+            Entry::Occupied(entry) => (*entry.get(), false),
+            Entry::Vacant(entry) => {
+                let idx = self.nodes.len();
+                self.nodes.push(entry.key().clone());
+                self.parents.push(Some(parent));
+                entry.insert(idx);
+                (idx, true)
+            }
+        }
+    }
+
+    /// The nodes from the start to the node at `idx`, both included.
+    fn path(&self, idx: usize) -> Vec<N> {
+        let mut path = vec![];
+        let mut current = Some(idx);
+        while let Some(idx) = current {
+            path.push(self.nodes[idx].clone());
+            current = self.parents[idx];
+        }
+        path.reverse();
+        path
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Visits the nodes reachable from `start` in breadth-first order, together with their distance in steps.
+///
+/// ```
+/// # use advent_of_code::graph::bfs;
+/// let reachable: Vec<(u32, usize)> = bfs(1, |&n| [n * 2, n * 3].into_iter().filter(|&n| n < 10)).collect();
+/// assert_eq!(reachable, vec![(1, 0), (2, 1), (3, 1), (4, 2), (6, 2), (9, 2), (8, 3)]);
+/// ```
+pub fn bfs<N, I, S>(start: N, successors: S) -> Bfs<N, S>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+{
+    Bfs {
+        queue: VecDeque::from([(start.clone(), 0)]),
+        seen: HashSet::from([start]),
+        successors,
+    }
+}
+
+pub struct Bfs<N, S> {
+    queue: VecDeque<(N, usize)>,
+    seen: HashSet<N>,
+    successors: S,
+}
+
+impl<N, I, S> Iterator for Bfs<N, S>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+{
+    type Item = (N, usize);
+
+    fn next(&mut self) -> Option<(N, usize)> {
+        let (node, distance) = self.queue.pop_front()?;
+        for next in (self.successors)(&node) {
+            if self.seen.insert(next.clone()) {
+                self.queue.push_back((next, distance + 1));
+            }
+        }
+        Some((node, distance))
+    }
+}
+
+/// Visits the nodes reachable from `start` in depth-first pre-order.
+pub fn dfs<N, I, S>(start: N, successors: S) -> Dfs<N, S>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+{
+    Dfs {
+        stack: vec![start],
+        seen: HashSet::new(),
+        successors,
+    }
+}
+
+pub struct Dfs<N, S> {
+    stack: Vec<N>,
+    seen: HashSet<N>,
+    successors: S,
+}
+
+impl<N, I, S> Iterator for Dfs<N, S>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<N> {
+        while let Some(node) = self.stack.pop() {
+            if !self.seen.insert(node.clone()) {
+                continue;
+            }
+
+            // successors are pushed in reverse, so that the first one is visited first.
+            let mut next: Vec<N> = (self.successors)(&node)
+                .into_iter()
+                .filter(|n| !self.seen.contains(n))
+                .collect();
+            next.reverse();
+            self.stack.extend(next);
+
+            return Some(node);
+        }
+        None
+    }
+}
+
+/// The shortest path in steps from `start` to the first node satisfying `goal`, both included.
+pub fn bfs_path<N, I, S, G>(start: N, mut successors: S, mut goal: G) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    let mut discovered = Discovered::new(start);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(idx) = queue.pop_front() {
+        let node = discovered.nodes[idx].clone();
+        if goal(&node) {
+            return Some(discovered.path(idx));
+        }
+        for next in successors(&node) {
+            if let (next, true) = discovered.insert(next, idx) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The cheapest path from `start` to the first node satisfying `goal`, with its total cost.
+/// `successors` yields the neighbours of a node with the cost of moving there.
+pub fn dijkstra<N, C, I, S, G>(start: N, successors: S, goal: G) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    S: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    astar(start, successors, |_| C::default(), goal)
+}
+
+/// The cheapest cost of reaching every node reachable from `start`.
+pub fn dijkstra_all<N, C, I, S>(start: N, mut successors: S) -> HashMap<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    S: FnMut(&N) -> I,
+{
+    let mut costs = HashMap::from([(start.clone(), C::default())]);
+    let mut discovered = Discovered::new(start);
+    let mut heap = BinaryHeap::from([Reverse((C::default(), 0))]);
+    let mut done = HashSet::new();
+
+    while let Some(Reverse((cost, idx))) = heap.pop() {
+        if !done.insert(idx) {
+            continue;
+        }
+        let node = discovered.nodes[idx].clone();
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            match costs.get(&next) {
+                Some(&known) if known <= next_cost => {}
+                _ => {
+                    costs.insert(next.clone(), next_cost);
+                    let (next, _) = discovered.insert(next, idx);
+                    heap.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+    }
+
+    costs
+}
+
+/// Like [`dijkstra`], but nodes are explored in order of their cost plus `heuristic`, an estimate of the
+/// remaining cost. The path is the cheapest one as long as the heuristic never overestimates.
+pub fn astar<N, C, I, S, H, G>(
+    start: N,
+    mut successors: S,
+    mut heuristic: H,
+    mut goal: G,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    S: FnMut(&N) -> I,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    let mut discovered = Discovered::new(start);
+    let mut costs = vec![C::default()];
+
+    while let Some(Reverse((_, cost, idx))) = heap.pop() {
+        // stale entries were superseded by a cheaper way to the same node.
+        if cost > costs[idx] {
+            continue;
+        }
+
+        let node = discovered.nodes[idx].clone();
+        if goal(&node) {
+            return Some((discovered.path(idx), cost));
+        }
+
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            let (next_idx, is_new) = discovered.insert(next, idx);
+
+            if is_new {
+                costs.push(next_cost);
+            } else if next_cost < costs[next_idx] {
+                costs[next_idx] = next_cost;
+                discovered.parents[next_idx] = Some(idx);
+            } else {
+                continue;
+            }
+
+            let estimate = next_cost + heuristic(&discovered.nodes[next_idx]);
+            heap.push(Reverse((estimate, next_cost, next_idx)));
+        }
+    }
+
+    None
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Groups `nodes` into the sets of nodes that are reachable from each other.
+/// `successors` must describe an undirected graph, i.e. edges have to be reported in both directions.
+pub fn connected_components<N, I, S>(
+    nodes: impl IntoIterator<Item = N>,
+    mut successors: S,
+) -> Vec<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+{
+    let mut seen = HashSet::new();
+    let mut components = vec![];
+
+    for node in nodes {
+        if seen.contains(&node) {
+            continue;
+        }
+        let component: Vec<N> = bfs(node, &mut successors).map(|(n, _)| n).collect();
+        seen.extend(component.iter().cloned());
+        components.push(component);
+    }
+
+    components
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Finds the cycle of the sequence `start, f(start), f(f(start)), ...` using Brent's algorithm.
+/// Returns the number of values before the cycle starts and the length of the cycle.
+///
+/// ```
+/// # use advent_of_code::graph::brent;
+/// // 3, 9, 5, 4, 1, 3, 9, ... for x -> x * 3 % 11
+/// assert_eq!(brent(3u32, |x| x * 3 % 11), (0, 5));
+/// ```
+pub fn brent<T, F>(start: T, mut f: F) -> (usize, usize)
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T) -> T,
+{
+    // find the cycle length by moving the hare ahead in doubling steps.
+    let mut power = 1;
+    let mut cycle_len = 1;
+    let mut tortoise = start.clone();
+    let mut hare = f(&start);
+    while tortoise != hare {
+        if power == cycle_len {
+            tortoise = hare.clone();
+            power *= 2;
+            cycle_len = 0;
+        }
+        hare = f(&hare);
+        cycle_len += 1;
+    }
+
+    // a hare `cycle_len` steps ahead meets the tortoise where the cycle starts.
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..cycle_len {
+        hare = f(&hare);
+    }
+    let mut prefix_len = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        prefix_len += 1;
+    }
+
+    (prefix_len, cycle_len)
+}
+
+/// Like [`brent`], but using Floyd's tortoise and hare, which needs more evaluations of `f`.
+pub fn floyd<T, F>(start: T, mut f: F) -> (usize, usize)
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T) -> T,
+{
+    let mut tortoise = f(&start);
+    let mut hare = f(&tortoise);
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        let step = f(&hare);
+        hare = f(&step);
+    }
+
+    // the distance of the meeting point from the start is a multiple of the cycle length.
+    let mut tortoise = start;
+    let mut prefix_len = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        prefix_len += 1;
+    }
+
+    let mut cycle_len = 1;
+    let mut hare = f(&tortoise);
+    while tortoise != hare {
+        hare = f(&hare);
+        cycle_len += 1;
+    }
+
+    (prefix_len, cycle_len)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        astar, bfs, bfs_path, brent, connected_components, dfs, dijkstra, dijkstra_all, floyd,
+    };
+    use crate::grid::{Grid, Point};
+    use std::collections::HashMap;
+
+    const MAZE: &str = "\
+S.#.....
+.##.###.
+....#...
+.##...#E
+...##...";
+
+    fn open_neighbours(grid: &Grid<char>, point: Point) -> Vec<Point> {
+        grid.neighbours_4(point)
+            .filter(|p| grid[*p] != '#')
+            .collect()
+    }
+
+    #[test]
+    fn visits_in_breadth_first_order() {
+        let grid: Grid<char> = MAZE.parse().unwrap();
+        let start = grid.position(&'S').unwrap();
+        let end = grid.position(&'E').unwrap();
+
+        let distances: HashMap<Point, usize> = bfs(start, |p| open_neighbours(&grid, *p)).collect();
+        assert_eq!(distances[&end], 12);
+        assert_eq!(distances.len(), grid.find_all(|c| *c != '#').count());
+
+        let path = bfs_path(start, |p| open_neighbours(&grid, *p), |p| *p == end).unwrap();
+        assert_eq!(path.len(), 13);
+        assert_eq!((path[0], path[12]), (start, end));
+        assert!(path.windows(2).all(|w| w[0].manhattan_distance(w[1]) == 1));
+
+        assert_eq!(
+            bfs_path(start, |p| open_neighbours(&grid, *p), |_| false),
+            None
+        );
+    }
+
+    #[test]
+    fn visits_in_depth_first_order() {
+        let tree = |n: &u32| {
+            if *n < 4 {
+                vec![n * 2, n * 2 + 1]
+            } else {
+                vec![]
+            }
+        };
+        let order: Vec<u32> = dfs(1, tree).collect();
+        assert_eq!(order, vec![1, 2, 4, 5, 3, 6, 7]);
+
+        let cyclic: Vec<u32> = dfs(0, |n| [(n + 1) % 3]).collect();
+        assert_eq!(cyclic, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn finds_cheapest_paths() {
+        let edges: HashMap<char, Vec<(char, u32)>> = HashMap::from([
+            ('a', vec![('b', 7), ('c', 9), ('f', 14)]),
+            ('b', vec![('a', 7), ('c', 10), ('d', 15)]),
+            ('c', vec![('a', 9), ('b', 10), ('d', 11), ('f', 2)]),
+            ('d', vec![('b', 15), ('c', 11), ('e', 6)]),
+            ('e', vec![('d', 6), ('f', 9)]),
+            ('f', vec![('a', 14), ('c', 2), ('e', 9)]),
+            ('g', vec![]),
+        ]);
+        let successors = |n: &char| edges[n].clone();
+
+        assert_eq!(
+            dijkstra('a', successors, |n| *n == 'e'),
+            Some((vec!['a', 'c', 'f', 'e'], 20))
+        );
+        assert_eq!(dijkstra('a', successors, |n| *n == 'g'), None);
+
+        let costs = dijkstra_all('a', successors);
+        assert_eq!(costs.len(), 6);
+        assert_eq!((costs[&'a'], costs[&'d'], costs[&'f']), (0, 20, 11));
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let grid = Grid::parse_with("13211\n91919\n11311\n19991\n11111", |c| {
+            c.to_digit(10).unwrap() as u64
+        })
+        .unwrap();
+        let (start, end) = (Point::new(0, 0), Point::new(4, 4));
+        let successors = |p: &Point| {
+            grid.neighbours_4(*p)
+                .map(|n| (n, grid[n]))
+                .collect::<Vec<_>>()
+        };
+
+        let (path, cost) = dijkstra(start, successors, |p| *p == end).unwrap();
+        let heuristic = |p: &Point| p.manhattan_distance(end) as u64;
+        let (astar_path, astar_cost) = astar(start, successors, heuristic, |p| *p == end).unwrap();
+
+        assert_eq!(cost, 11);
+        assert_eq!(astar_cost, cost);
+        assert_eq!(
+            astar_path.iter().skip(1).map(|p| grid[*p]).sum::<u64>(),
+            cost
+        );
+        assert_eq!(path.iter().skip(1).map(|p| grid[*p]).sum::<u64>(), cost);
+    }
+
+    #[test]
+    fn groups_connected_components() {
+        let edges = [(1, 2), (2, 3), (4, 5), (7, 7)];
+        let neighbours = |n: &u32| {
+            edges
+                .iter()
+                .filter_map(|&(a, b)| match (a == *n, b == *n) {
+                    (true, _) => Some(b),
+                    (_, true) => Some(a),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut components = connected_components(1..=7, neighbours);
+        components.iter_mut().for_each(|c| c.sort());
+        assert_eq!(
+            components,
+            vec![vec![1, 2, 3], vec![4, 5], vec![6], vec![7]]
+        );
+    }
+
+    #[test]
+    fn detects_cycles() {
+        // brute force: remember the first index of every value.
+        fn cycle(start: u64, f: impl Fn(&u64) -> u64) -> (usize, usize) {
+            let mut seen = HashMap::new();
+            let mut x = start;
+            for idx in 0.. {
+                if let Some(first) = seen.insert(x, idx) {
+                    return (first, idx - first);
+                }
+                x = f(&x);
+            }
+            unreachable!()
+        }
+
+        for modulus in 1..200 {
+            for start in [0, 1, 2, modulus / 2] {
+                let f = |x: &u64| (x * x + 1) % modulus;
+                let expected = cycle(start, f);
+                assert_eq!(
+                    brent(start, f),
+                    expected,
+                    "x^2 + 1 mod {modulus} from {start}"
+                );
+                assert_eq!(
+                    floyd(start, f),
+                    expected,
+                    "x^2 + 1 mod {modulus} from {start}"
+                );
+            }
+        }
+
+        // the example of the doc comment, as doctests do not run.
+        assert_eq!(brent(3u32, |x| x * 3 % 11), (0, 5));
+        assert_eq!(brent(5, |x| *x), (0, 1));
+        assert_eq!(floyd(9, |x| if *x > 0 { x - 1 } else { 0 }), (9, 1));
+    }
+}
@@ -0,0 +1,536 @@
+//! Two-dimensional grids as used by many puzzles, e.g. maps of pipes or galaxies.
+//!
+//! Coordinates are signed, so that stepping off the grid yields a [`Point`] that [`Grid::get`]
+//! rejects instead of an underflow.
+use std::fmt::Display;
+use std::ops::{Add, Index, IndexMut, Sub};
+use std::str::FromStr;
+
+/// A position on a grid. `x` is the column and grows to the right, `y` is the row and grows downwards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    /// The neighbouring point in a direction.
+    #[must_use]
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.delta()
+    }
+
+    pub fn manhattan_distance(self, other: Point) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The points above, to the right, below and to the left, in this order.
+    pub fn neighbours_4(self) -> impl Iterator<Item = Point> {
+        Direction::ALL.into_iter().map(move |d| self.step(d))
+    }
+
+    /// The horizontally, vertically and diagonally adjacent points, clockwise starting above.
+    pub fn neighbours_8(self) -> impl Iterator<Item = Point> {
+        [
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ]
+        .into_iter()
+        .map(move |(x, y)| self + Point::new(x, y))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// One of the four directions on a grid. `Up` decreases `y`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions, clockwise starting with `Up`.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// The offset of a single step in this direction.
+    pub fn delta(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+        }
+    }
+
+    #[must_use]
+    pub fn opposite(self) -> Self {
+        self.turn_right().turn_right()
+    }
+
+    #[must_use]
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    #[must_use]
+    pub fn turn_left(self) -> Self {
+        self.opposite().turn_right()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// A row's length differs from the first row's.
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A cell could not be converted, e.g. an unexpected character.
+    InvalidCell { point: Point, message: String },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(f, "row {} has {found} cells, expected {expected}.", row + 1),
+            Error::InvalidCell { point, message } => {
+                write!(
+                    f,
+                    "invalid cell at row {}, column {}: {message}",
+                    point.y + 1,
+                    point.x + 1
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid where every cell holds `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// The grid mirrored along its main diagonal, i.e. rows become columns.
+    #[must_use]
+    pub fn transpose(&self) -> Self {
+        Self {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .flat_map(|x| self.column(x).cloned())
+                .collect(),
+        }
+    }
+
+    /// The grid rotated by 90 degrees clockwise.
+    #[must_use]
+    pub fn rotate_clockwise(&self) -> Self {
+        Self {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .flat_map(|x| self.column(x).rev().cloned())
+                .collect(),
+        }
+    }
+
+    /// The grid rotated by 90 degrees counter-clockwise.
+    #[must_use]
+    pub fn rotate_counter_clockwise(&self) -> Self {
+        Self {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .rev()
+                .flat_map(|x| self.column(x).cloned())
+                .collect(),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from its cells in row order. Returns `None` if their number is not `width * height`.
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Option<Self> {
+        (cells.len() == width * height).then_some(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses a grid from lines of text, converting each character with `f`.
+    /// Trailing empty lines and `\r\n` line endings are ignored.
+    pub fn parse_with(s: &str, mut f: impl FnMut(char) -> T) -> Result<Self, Error> {
+        Self::try_parse_with(s, |c| Ok::<T, String>(f(c)))
+    }
+
+    /// Like [`Grid::parse_with`], but the conversion can reject characters.
+    pub fn try_parse_with<E: Display>(
+        s: &str,
+        mut f: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Self, Error> {
+        let lines: Vec<&str> = s.trim_end_matches(['\n', '\r']).lines().collect();
+        let width = lines.first().map_or(0, |line| line.chars().count());
+
+        let mut cells = Vec::with_capacity(width * lines.len());
+
+        for (y, line) in lines.iter().enumerate() {
+            let row_start = cells.len();
+
+            for (x, c) in line.chars().enumerate() {
+                let cell = f(c).map_err(|e| Error::InvalidCell {
+                    point: Point::new(x as i64, y as i64),
+                    message: e.to_string(),
+                })?;
+                cells.push(cell);
+            }
+
+            let found = cells.len() - row_start;
+            if found != width {
+                return Err(Error::RaggedRow {
+                    row: y,
+                    expected: width,
+                    found,
+                });
+            }
+        }
+
+        Ok(Self {
+            width,
+            height: lines.len(),
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (0..self.width as i64).contains(&point.x) && (0..self.height as i64).contains(&point.y)
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        self.contains(point)
+            .then(|| point.y as usize * self.width + point.x as usize)
+    }
+
+    fn point_of(&self, index: usize) -> Point {
+        Point::new((index % self.width) as i64, (index / self.width) as i64)
+    }
+
+    /// The cell at `point`, or `None` if it is outside of the grid.
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|idx| &mut self.cells[idx])
+    }
+
+    /// The neighbours of `point` in the four directions that are inside of the grid.
+    pub fn neighbours_4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbours_4().filter(|p| self.contains(*p))
+    }
+
+    /// The neighbours of `point`, including diagonal ones, that are inside of the grid.
+    pub fn neighbours_8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbours_8().filter(|p| self.contains(*p))
+    }
+
+    /// The cells of row `y`. Panics if the row is out of bounds.
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// The cells of column `x`, top to bottom. Panics if the column is out of bounds.
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(x < self.width, "column {x} is out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// All points of the grid in row order.
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.cells.len()).map(|idx| self.point_of(idx))
+    }
+
+    /// All cells with their points, in row order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(idx, cell)| (self.point_of(idx), cell))
+    }
+
+    /// The point of the first cell, in row order, that satisfies `predicate`.
+    pub fn find(&self, predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|idx| self.point_of(idx))
+    }
+
+    /// The points of all cells that satisfy `predicate`, in row order.
+    pub fn find_all<'a>(
+        &'a self,
+        mut predicate: impl FnMut(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Point> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(point, _)| point)
+    }
+
+    /// The point of the first cell, in row order, that equals `value`.
+    pub fn position(&self, value: &T) -> Option<Point>
+    where
+        T: PartialEq,
+    {
+        self.find(|cell| cell == value)
+    }
+
+    /// A grid of the same size with every cell converted by `f`.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("point {point} is outside of the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("point {point} is outside of the grid"))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse_with(s, |c| c)
+    }
+}
+
+/// Displays the cells row by row, without separators and without a trailing newline.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Direction, Error, Grid, Point};
+    use std::str::FromStr;
+
+    fn get_mock_grid() -> Grid<char> {
+        Grid::from_str("abc\r\ndef\n").unwrap()
+    }
+
+    #[test]
+    fn parses_grids() {
+        let grid = get_mock_grid();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[Point::new(2, 1)], 'f');
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        assert_eq!(grid.to_string(), "abc\ndef");
+    }
+
+    #[test]
+    fn parses_digits() {
+        let grid = Grid::try_parse_with("12\n34", |c| c.to_digit(10).ok_or("not a digit")).unwrap();
+        assert_eq!(grid.row(1), &[3, 4]);
+
+        let err = Grid::try_parse_with("12\n3x", |c| c.to_digit(10).ok_or("not a digit"));
+        assert_eq!(
+            err,
+            Err(Error::InvalidCell {
+                point: Point::new(1, 1),
+                message: "not a digit".into()
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        assert_eq!(
+            Grid::from_str("abc\nde"),
+            Err(Error::RaggedRow {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn parses_empty_input() {
+        let grid = Grid::from_str("").unwrap();
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+        assert_eq!(grid.points().count(), 0);
+    }
+
+    #[test]
+    fn yields_neighbours_within_bounds() {
+        let grid = get_mock_grid();
+        let corner: Vec<Point> = grid.neighbours_4(Point::new(0, 0)).collect();
+        assert_eq!(corner, vec![Point::new(1, 0), Point::new(0, 1)]);
+        assert_eq!(grid.neighbours_8(Point::new(1, 0)).count(), 5);
+        assert_eq!(grid.neighbours_8(Point::new(1, 1)).count(), 5);
+        assert_eq!(Point::new(5, 5).neighbours_8().count(), 8);
+    }
+
+    #[test]
+    fn views_rows_and_columns() {
+        let grid = get_mock_grid();
+        assert_eq!(grid.row(0), &['a', 'b', 'c']);
+        let column: String = grid.column(1).collect();
+        assert_eq!(column, "be");
+        let columns: Vec<String> = grid.columns().map(|c| c.collect()).collect();
+        assert_eq!(columns, vec!["ad", "be", "cf"]);
+    }
+
+    #[test]
+    fn transposes_and_rotates() {
+        let grid = get_mock_grid();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_counter_clockwise().to_string(), "cf\nbe\nad");
+        assert_eq!(
+            grid.rotate_clockwise().rotate_counter_clockwise(),
+            grid.clone()
+        );
+        assert_eq!(grid.transpose().transpose(), grid);
+    }
+
+    #[test]
+    fn finds_cells() {
+        let grid = Grid::from_str("#.#\n.#.").unwrap();
+        assert_eq!(grid.position(&'.'), Some(Point::new(1, 0)));
+        assert_eq!(grid.find(|c| *c == 'x'), None);
+        let galaxies: Vec<Point> = grid.find_all(|c| *c == '#').collect();
+        assert_eq!(
+            galaxies,
+            vec![Point::new(0, 0), Point::new(2, 0), Point::new(1, 1)]
+        );
+    }
+
+    #[test]
+    fn maps_and_mutates_cells() {
+        let mut grid = get_mock_grid().map(|c| c.to_ascii_uppercase());
+        grid[Point::new(0, 0)] = 'x';
+        assert_eq!(grid.to_string(), "xBC\nDEF");
+        assert_eq!(Grid::new(2, 1, 0).to_string(), "00");
+    }
+
+    #[test]
+    fn turns_directions() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        assert_eq!(Point::new(1, 1).step(Direction::Up), Point::new(1, 0));
+        assert_eq!(Point::new(1, 1).manhattan_distance(Point::new(-2, 3)), 5);
+    }
+}
@@ -0,0 +1,679 @@
+//! Half-open integer intervals, normalized sets of them and piecewise-linear maps between them,
+//! e.g. the seed ranges and almanac maps of day 5.
+//!
+//! Arithmetic happens in `i128`, so that shifting never overflows: values that would leave the
+//! range of the bound type are dropped instead.
+use crate::math::Overflow;
+use std::fmt::{Debug, Display};
+use std::marker::PhantomData;
+
+/// Integer types that can bound an [`Interval`]. Implemented for all integers up to 64 bits.
+pub trait Bound: Copy + Ord + Debug + Display {
+    const MIN: Self;
+    const MAX: Self;
+
+    fn to_i128(self) -> i128;
+
+    /// Converts back from `i128`, or `None` if the value is out of range.
+    fn from_i128(value: i128) -> Option<Self>;
+
+    /// Converts back from `i128`, clamping to the range of the type.
+    fn from_i128_clamped(value: i128) -> Self;
+}
+
+macro_rules! impl_bound {
+    ($( $type:ty ),*) => {
+        $(
+            impl Bound for $type {
+                const MIN: Self = <$type>::MIN;
+                const MAX: Self = <$type>::MAX;
+
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+
+                fn from_i128(value: i128) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+
+                fn from_i128_clamped(value: i128) -> Self {
+                    value.clamp(Self::MIN as i128, Self::MAX as i128) as Self
+                }
+            }
+        )*
+    };
+}
+
+impl_bound!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/* -------------------------------------------------------------------------- */
+
+/// The values `start..end`. Like [`std::ops::Range`], but `Copy` and with set operations.
+/// The bounds are stored as `i128`, so that the end may lie one past `T::MAX` and every value of
+/// `T`, including `T::MAX`, can be contained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    start: i128,
+    end: i128,
+    marker: PhantomData<T>,
+}
+
+impl<T: Bound> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self::from_bounds(start.to_i128(), end.to_i128())
+    }
+
+    /// The values from `first` up to and including `last`.
+    pub fn inclusive(first: T, last: T) -> Self {
+        Self::from_bounds(first.to_i128(), last.to_i128() + 1)
+    }
+
+    /// The interval of `len` values beginning at `start`, or `None` if it extends beyond `T::MAX`.
+    pub fn with_len(start: T, len: T) -> Option<Self> {
+        let end = start.to_i128() + len.to_i128();
+        (end <= T::MAX.to_i128() + 1).then(|| Self::from_bounds(start.to_i128(), end))
+    }
+
+    /// Clamps the bounds to the values of `T` and one past `T::MAX`.
+    fn from_bounds(start: i128, end: i128) -> Self {
+        let clamp = |bound: i128| bound.clamp(T::MIN.to_i128(), T::MAX.to_i128() + 1);
+        Self {
+            start: clamp(start),
+            end: clamp(end),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    /// The number of values in the interval.
+    pub fn len(&self) -> u128 {
+        if self.is_empty() {
+            0
+        } else {
+            (self.end - self.start) as u128
+        }
+    }
+
+    /// The smallest value, or `None` if the interval is empty.
+    pub fn first(&self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            T::from_i128(self.start)
+        }
+    }
+
+    /// The largest value, or `None` if the interval is empty.
+    pub fn last(&self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            T::from_i128(self.end - 1)
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        (self.start..self.end).contains(&value.to_i128())
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// The values contained in both intervals. May be empty.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self::from_bounds(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// The values of `self` below and above `other`. Either part may be empty.
+    pub fn difference(&self, other: &Self) -> (Self, Self) {
+        (
+            Self::from_bounds(self.start, self.end.min(other.start)),
+            Self::from_bounds(self.start.max(other.end), self.end),
+        )
+    }
+
+    /// Adds `delta` to every value. Values that would leave the range of `T` are dropped.
+    #[must_use]
+    pub fn shift(&self, delta: i128) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        Self::from_bounds(self.start + delta, self.end + delta)
+    }
+}
+
+impl<T: Bound> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {})", self.start, self.end)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A set of values stored as sorted, disjoint and non-adjacent intervals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { intervals: vec![] }
+    }
+}
+
+impl<T: Bound> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The set containing every value of the type.
+    pub fn full() -> Self {
+        Self::from(Interval::inclusive(T::MIN, T::MAX))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of values in the set.
+    pub fn len(&self) -> u128 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    /// The normalized intervals in ascending order.
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().and_then(Interval::first)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        // the last interval starting at or before `value` is the only candidate.
+        let idx = self
+            .intervals
+            .partition_point(|i| i.start <= value.to_i128());
+        idx > 0 && self.intervals[idx - 1].contains(value)
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        let mut merged = interval;
+        // intervals that overlap or touch the new one are absorbed.
+        let first = self.intervals.partition_point(|i| i.end < merged.start);
+        let last = self.intervals.partition_point(|i| i.start <= merged.end);
+
+        if first < last {
+            merged.start = merged.start.min(self.intervals[first].start);
+            merged.end = merged.end.max(self.intervals[last - 1].end);
+        }
+
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for interval in &other.intervals {
+            result.insert(*interval);
+        }
+        result
+    }
+
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = vec![];
+        let (mut a, mut b) = (0, 0);
+
+        while a < self.intervals.len() && b < other.intervals.len() {
+            let (x, y) = (self.intervals[a], other.intervals[b]);
+            let common = x.intersection(&y);
+            if !common.is_empty() {
+                intervals.push(common);
+            }
+            if x.end < y.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+
+        // intersections of normalized sets are normalized.
+        Self { intervals }
+    }
+
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = vec![];
+
+        for interval in &self.intervals {
+            let mut rest = *interval;
+            for removed in other.intervals.iter().filter(|r| r.overlaps(interval)) {
+                let (below, above) = rest.difference(removed);
+                if !below.is_empty() {
+                    intervals.push(below);
+                }
+                rest = above;
+            }
+            if !rest.is_empty() {
+                intervals.push(rest);
+            }
+        }
+
+        Self { intervals }
+    }
+
+    /// Adds `delta` to every value. Values that would leave the range of `T` are dropped.
+    #[must_use]
+    pub fn shift(&self, delta: i128) -> Self {
+        self.intervals.iter().map(|i| i.shift(delta)).collect()
+    }
+}
+
+impl<T: Bound> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(interval);
+        set
+    }
+}
+
+impl<T: Bound> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+impl<T: Bound> Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = self.intervals.iter().map(ToString::to_string).collect();
+        write!(f, "{{{}}}", parts.join(", "))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A piecewise-linear map: values inside a piece's interval are shifted by its delta,
+/// all other values map to themselves. Later pieces never override earlier ones.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalMap<T> {
+    /// Disjoint pieces, sorted by their start.
+    pieces: Vec<(Interval<T>, i128)>,
+}
+
+impl<T: Bound> IntervalMap<T> {
+    pub fn new() -> Self {
+        Self { pieces: vec![] }
+    }
+
+    /// The identity map.
+    pub fn identity() -> Self {
+        Self::new()
+    }
+
+    /// Maps the values of `source` by adding `delta`. Values already mapped by an earlier piece keep their mapping.
+    pub fn insert(&mut self, source: Interval<T>, delta: i128) {
+        let taken: IntervalSet<T> = self.pieces.iter().map(|(i, _)| *i).collect();
+        let free = IntervalSet::from(source).difference(&taken);
+
+        self.pieces
+            .extend(free.intervals().iter().map(|i| (*i, delta)));
+        self.normalize();
+    }
+
+    /// Like [`IntervalMap::insert`], in the form of day 5's almanac: `len` values from `source` map to `destination`.
+    /// Fails without changing the map if either range extends beyond `T::MAX`.
+    pub fn insert_range(&mut self, destination: T, source: T, len: T) -> Result<(), Overflow> {
+        let interval = Interval::with_len(source, len).ok_or(Overflow)?;
+        Interval::with_len(destination, len).ok_or(Overflow)?;
+        self.insert(interval, destination.to_i128() - source.to_i128());
+        Ok(())
+    }
+
+    /// The explicitly mapped pieces with their deltas, in ascending order.
+    pub fn pieces(&self) -> &[(Interval<T>, i128)] {
+        &self.pieces
+    }
+
+    /// The image of a single value, or `None` if it leaves the range of `T`.
+    pub fn apply(&self, value: T) -> Option<T> {
+        let idx = self
+            .pieces
+            .partition_point(|(i, _)| i.start <= value.to_i128());
+        match idx.checked_sub(1).map(|idx| self.pieces[idx]) {
+            Some((interval, delta)) if interval.contains(value) => {
+                T::from_i128(value.to_i128() + delta)
+            }
+            _ => Some(value),
+        }
+    }
+
+    /// The image of a whole set.
+    pub fn apply_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        self.total_pieces()
+            .into_iter()
+            .flat_map(|(piece, delta)| {
+                set.intersection(&IntervalSet::from(piece))
+                    .shift(delta)
+                    .intervals
+            })
+            .collect()
+    }
+
+    /// All values that map into `set`, e.g. the seeds that end up at a set of locations.
+    pub fn preimage(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        self.total_pieces()
+            .into_iter()
+            .flat_map(|(piece, delta)| {
+                set.intersection(&IntervalSet::from(piece.shift(delta)))
+                    .shift(-delta)
+                    .intervals
+            })
+            .collect()
+    }
+
+    /// The map that applies `self` first and `next` second. Values that `self` maps outside of
+    /// `T` stay unmapped in the composition as well.
+    #[must_use]
+    pub fn then(&self, next: &Self) -> Self {
+        let mut pieces = vec![];
+        let (min, max) = (T::MIN.to_i128(), T::MAX.to_i128() + 1);
+
+        for (first, first_delta) in self.total_pieces() {
+            // the image is not clamped, its parts outside of `T` keep a delta that leaves `T`.
+            let (image_start, image_end) = (first.start + first_delta, first.end + first_delta);
+            let mut push = |start: i128, end: i128, delta: i128| {
+                if start < end {
+                    let source = Interval::from_bounds(start - first_delta, end - first_delta);
+                    pieces.push((source, delta));
+                }
+            };
+
+            push(image_start, image_end.min(min), first_delta);
+            push(image_start.max(max), image_end, first_delta);
+            // the pieces of `next` cover all of `T`.
+            for (second, second_delta) in next.total_pieces() {
+                push(
+                    image_start.max(second.start),
+                    image_end.min(second.end),
+                    first_delta + second_delta,
+                );
+            }
+        }
+
+        let mut result = Self { pieces };
+        result.normalize();
+        result
+    }
+
+    /// The pieces, including identity pieces for the gaps between them, covering all of `T`.
+    fn total_pieces(&self) -> Vec<(Interval<T>, i128)> {
+        let mapped: IntervalSet<T> = self.pieces.iter().map(|(i, _)| *i).collect();
+        let mut pieces = self.pieces.clone();
+        pieces.extend(
+            IntervalSet::full()
+                .difference(&mapped)
+                .intervals()
+                .iter()
+                .map(|i| (*i, 0)),
+        );
+        pieces
+    }
+
+    /// Sorts the pieces, drops identity and empty pieces and merges adjacent pieces with equal deltas.
+    fn normalize(&mut self) {
+        self.pieces
+            .retain(|(i, delta)| !i.is_empty() && *delta != 0);
+        self.pieces.sort_unstable_by_key(|(i, _)| i.start);
+
+        let mut merged: Vec<(Interval<T>, i128)> = Vec::with_capacity(self.pieces.len());
+        for (interval, delta) in self.pieces.drain(..) {
+            match merged.last_mut() {
+                Some((last, last_delta)) if last.end == interval.start && *last_delta == delta => {
+                    last.end = interval.end;
+                }
+                _ => merged.push((interval, delta)),
+            }
+        }
+        self.pieces = merged;
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Interval, IntervalMap, IntervalSet, Overflow};
+    use crate::rng::Rng;
+
+    /// Small intervals anywhere in `u8`, including ones touching the bounds.
+    fn random_interval(rng: &mut Rng) -> Interval<u8> {
+        let start = match rng.below(4) {
+            0 => 0,
+            1 => 250,
+            _ => rng.below(256) as u8,
+        };
+        // up to the end of `u8`, so that `u8::MAX` is covered as well.
+        let len = (rng.below(40) as u16).min(256 - start as u16);
+        Interval::with_len(start, len as u8).unwrap_or(Interval::inclusive(start, u8::MAX))
+    }
+
+    fn random_set(rng: &mut Rng) -> IntervalSet<u8> {
+        (0..rng.below(5)).map(|_| random_interval(rng)).collect()
+    }
+
+    fn random_map(rng: &mut Rng) -> IntervalMap<u8> {
+        let mut map = IntervalMap::new();
+        for _ in 0..rng.below(4) {
+            let delta = rng.below(101) as i128 - 50;
+            map.insert(random_interval(rng), delta);
+        }
+        map
+    }
+
+    fn values(set: &IntervalSet<u8>) -> Vec<bool> {
+        (0..=255u8).map(|x| set.contains(x)).collect()
+    }
+
+    fn is_normalized(set: &IntervalSet<u8>) -> bool {
+        set.intervals().iter().all(|i| !i.is_empty())
+            && set.intervals().windows(2).all(|w| w[0].end < w[1].start)
+    }
+
+    const ITERATIONS: usize = 2000;
+
+    #[test]
+    fn interval_basics() {
+        let a = Interval::new(10u32, 20);
+        assert_eq!(a.len(), 10);
+        assert!(a.contains(10) && !a.contains(20));
+        assert_eq!(
+            a.intersection(&Interval::new(15, 30)),
+            Interval::new(15, 20)
+        );
+        assert!(a.intersection(&Interval::new(20, 30)).is_empty());
+        assert_eq!(
+            a.difference(&Interval::new(12, 15)),
+            (Interval::new(10, 12), Interval::new(15, 20))
+        );
+        assert_eq!(a.to_string(), "[10, 20)");
+    }
+
+    #[test]
+    fn handles_type_bounds() {
+        assert_eq!(
+            Interval::with_len(u32::MAX - 5, 5),
+            Some(Interval::new(u32::MAX - 5, u32::MAX))
+        );
+        let top = Interval::with_len(u32::MAX - 5, 6).unwrap();
+        assert_eq!(top, Interval::inclusive(u32::MAX - 5, u32::MAX));
+        assert!(top.contains(u32::MAX));
+        assert_eq!((top.len(), top.last()), (6, Some(u32::MAX)));
+        assert_eq!(top.to_string(), "[4294967290, 4294967296)");
+        assert_eq!(Interval::with_len(u32::MAX - 5, 7), None);
+        assert_eq!(Interval::new(u32::MAX - 10, u32::MAX).shift(5), top);
+        assert_eq!(IntervalSet::<u8>::full().len(), 256);
+        assert_eq!(Interval::new(3u32, 10).shift(-5), Interval::new(0, 5));
+        assert!(Interval::new(3u32, 10).shift(-50).is_empty());
+        assert_eq!(Interval::new(i64::MIN, i64::MAX).len(), u64::MAX as u128);
+    }
+
+    #[test]
+    fn set_operations_match_values() {
+        let mut rng = Rng::new(0x5eed);
+
+        for _ in 0..ITERATIONS {
+            let (a, b) = (random_set(&mut rng), random_set(&mut rng));
+            let (va, vb) = (values(&a), values(&b));
+
+            for (result, op) in [
+                (a.union(&b), (|x, y| x || y) as fn(bool, bool) -> bool),
+                (a.intersection(&b), |x, y| x && y),
+                (a.difference(&b), |x, y| x && !y),
+            ] {
+                assert!(is_normalized(&result), "{result} is not normalized");
+                let expected: Vec<bool> = va.iter().zip(&vb).map(|(x, y)| op(*x, *y)).collect();
+                assert_eq!(values(&result), expected, "{a} and {b}");
+                assert_eq!(
+                    result.len(),
+                    expected.iter().filter(|x| **x).count() as u128
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn shifting_drops_values_outside_of_bounds() {
+        let mut rng = Rng::new(0xdecaf);
+
+        for _ in 0..ITERATIONS {
+            let set = random_set(&mut rng);
+            let delta = rng.below(601) as i128 - 300;
+            let shifted = set.shift(delta);
+            assert!(is_normalized(&shifted));
+
+            for x in 0..=255u8 {
+                let source = x as i128 - delta;
+                let expected = (0..=255).contains(&source) && set.contains(source as u8);
+                assert_eq!(shifted.contains(x), expected, "{set} shifted by {delta}");
+            }
+        }
+    }
+
+    #[test]
+    fn maps_sets_pointwise() {
+        let mut rng = Rng::new(0xa1b2c3);
+
+        for _ in 0..ITERATIONS {
+            let (map, set) = (random_map(&mut rng), random_set(&mut rng));
+            let image = map.apply_set(&set);
+            assert!(is_normalized(&image));
+
+            let mut expected = vec![false; 256];
+            for x in (0..=255u8).filter(|x| set.contains(*x)) {
+                // values shifted beyond `u8::MAX` are dropped.
+                if let Some(y) = map.apply(x) {
+                    expected[y as usize] = true;
+                }
+            }
+            assert_eq!(values(&image), expected, "{set} mapped by {map:?}");
+        }
+    }
+
+    #[test]
+    fn preimages_match_values() {
+        let mut rng = Rng::new(0xfeed);
+
+        for _ in 0..ITERATIONS {
+            let (map, set) = (random_map(&mut rng), random_set(&mut rng));
+            let preimage = map.preimage(&set);
+
+            for x in 0..=255u8 {
+                let expected = map.apply(x).is_some_and(|y| set.contains(y));
+                assert_eq!(preimage.contains(x), expected, "{set} under {map:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn composes_maps() {
+        let mut rng = Rng::new(0xc0ffee);
+
+        for _ in 0..ITERATIONS {
+            let (first, second) = (random_map(&mut rng), random_map(&mut rng));
+            let composed = first.then(&second);
+
+            for x in 0..=255u8 {
+                let expected = first.apply(x).and_then(|y| second.apply(y));
+                assert_eq!(
+                    composed.apply(x),
+                    expected,
+                    "{first:?} then {second:?} at {x}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn composing_keeps_values_outside_of_bounds_unmapped() {
+        let mut leaving = IntervalMap::new();
+        leaving.insert(Interval::new(250u8, 255), 10);
+        assert_eq!(leaving.apply(252), None);
+        assert_eq!(leaving.then(&IntervalMap::new()).apply(252), None);
+        assert_eq!(IntervalMap::new().then(&leaving).apply(252), None);
+        assert_eq!(leaving.then(&leaving).apply(250), None);
+        assert_eq!(leaving.then(&leaving).apply(245), Some(245));
+
+        let mut onto_max = IntervalMap::new();
+        onto_max.insert(Interval::new(0u8, 1), 255);
+        assert_eq!(onto_max.apply(0), Some(255));
+        assert_eq!(onto_max.then(&IntervalMap::new()).apply(0), Some(255));
+        assert_eq!(onto_max.then(&leaving).apply(0), Some(255));
+    }
+
+    #[test]
+    fn inserts_ranges_up_to_the_type_bounds() {
+        let mut map = IntervalMap::new();
+        assert_eq!(map.insert_range(0u32, 4294967290, 6), Ok(()));
+        assert_eq!(map.apply(u32::MAX), Some(5));
+        assert_eq!(map.pieces().len(), 1);
+
+        assert_eq!(map.insert_range(0, 4294967290, 7), Err(Overflow));
+        assert_eq!(map.insert_range(4294967290, 0, 7), Err(Overflow));
+        assert_eq!(map.pieces().len(), 1);
+    }
+
+    #[test]
+    fn earlier_pieces_take_precedence() {
+        let mut map = IntervalMap::new();
+        map.insert_range(50u64, 98, 2).unwrap();
+        map.insert_range(52, 50, 48).unwrap();
+        map.insert(Interval::new(40, 60), 1000);
+
+        assert_eq!(map.apply(98), Some(50));
+        assert_eq!(map.apply(50), Some(52));
+        assert_eq!(map.apply(45), Some(1045));
+        assert_eq!(map.apply(10), Some(10));
+        assert_eq!(map.apply(100), Some(100));
+
+        let seeds = IntervalSet::from(Interval::new(79u64, 93));
+        assert_eq!(
+            map.apply_set(&seeds),
+            IntervalSet::from(Interval::new(81, 95))
+        );
+    }
+}
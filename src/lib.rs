@@ -1,3 +1,10 @@
+pub mod graph;
+pub mod grid;
+pub mod interval;
+pub mod math;
+pub mod memo;
+pub mod parse;
+pub mod rng;
 pub mod template;
 
 // Use this file to add helper functions and additional modules.
@@ -1,4 +1,7 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, completions, download, help, read, scaffold, solve, status, time,
+};
+use advent_of_code::template::runner::RunArguments;
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
@@ -7,8 +10,9 @@ use advent_of_code::template::Day;
 use std::process;
 
 mod args {
-    use advent_of_code::template::Day;
-    use std::process;
+    use advent_of_code::template::commands::{completions::Shell, help};
+    use advent_of_code::template::{Config, Day, Param, Part};
+    use std::{env, process};
 
     pub enum AppArguments {
         Download {
@@ -26,7 +30,10 @@ mod args {
             release: bool,
             time: bool,
             dhat: bool,
-            submit: Option<u8>,
+            dhat_part: Option<Part>,
+            submit: Option<Part>,
+            params: Vec<Param>,
+            ocr: bool,
         },
         All {
             release: bool,
@@ -36,19 +43,88 @@ mod args {
             all: bool,
             day: Option<Day>,
         },
+        Status {
+            json: bool,
+        },
+        Completions {
+            shell: Shell,
+        },
+        Help {
+            command: Option<String>,
+        },
         #[cfg(feature = "today")]
         Today,
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
+        let wants_help = args.contains(["-h", "--help"]);
+
+        // global overrides are mirrored to env vars, so that child processes pick them up as well.
+        if let Some(path) = args.opt_value_from_str::<_, String>("--config")? {
+            env::set_var("AOC_CONFIG", path);
+        }
+        if let Some(year) = args.opt_value_from_str::<_, u16>("--year")? {
+            env::set_var("AOC_YEAR", year.to_string());
+        }
+        for (flag, var) in [
+            ("--data-dir", "AOC_DATA_DIR"),
+            ("--readme", "AOC_README"),
+            ("--timings-file", "AOC_TIMINGS_FILE"),
+            ("--answers-file", "AOC_ANSWERS_FILE"),
+        ] {
+            if let Some(path) = args.opt_value_from_str::<_, String>(flag)? {
+                env::set_var(var, path);
+            }
+        }
+
+        let Some(command) = args.subcommand()? else {
+            if wants_help {
+                return Ok(AppArguments::Help { command: None });
+            }
+            eprintln!("No command specified.");
+            eprintln!("Run `cargo run -- help` to list the available commands.");
+            process::exit(1);
+        };
 
-        let app_args = match args.subcommand()?.as_deref() {
-            Some("all") => AppArguments::All {
+        if wants_help {
+            return Ok(AppArguments::Help {
+                command: Some(command),
+            });
+        }
+
+        let app_args = match parse_command(&command, &mut args) {
+            Ok(app_args) => app_args,
+            Err(err) => {
+                eprintln!("Error: {err}");
+                if let Some(usage) = help::usage(&command) {
+                    eprintln!("{usage}");
+                }
+                process::exit(1);
+            }
+        };
+
+        let remaining = args.finish();
+        if !remaining.is_empty() {
+            eprintln!("Warning: unknown argument(s): {remaining:?}.");
+            if let Some(usage) = help::usage(&command) {
+                eprintln!("{usage}");
+            }
+        }
+
+        Ok(app_args)
+    }
+
+    fn parse_command(
+        command: &str,
+        args: &mut pico_args::Arguments,
+    ) -> Result<AppArguments, pico_args::Error> {
+        let app_args = match command {
+            "all" => AppArguments::All {
                 release: args.contains("--release"),
                 time: args.contains("--time"),
             },
-            Some("time") => {
+            "time" => {
                 let all = args.contains("--all");
 
                 AppArguments::Time {
@@ -56,42 +132,61 @@ mod args {
                     day: args.opt_free_from_str()?,
                 }
             }
-            Some("download") => AppArguments::Download {
+            "download" => AppArguments::Download {
                 day: args.free_from_str()?,
             },
-            Some("read") => AppArguments::Read {
+            "read" => AppArguments::Read {
                 day: args.free_from_str()?,
             },
-            Some("scaffold") => AppArguments::Scaffold {
+            "scaffold" => AppArguments::Scaffold {
                 day: args.free_from_str()?,
                 download: args.contains("--download"),
             },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                time: args.contains("--time"),
-                dhat: args.contains("--dhat"),
+            "solve" => {
+                let defaults = &Config::get().solve;
+                AppArguments::Solve {
+                    day: args.free_from_str()?,
+                    release: toggle(args, "--release", "--debug", defaults.release),
+                    submit: args.opt_value_from_str("--submit")?,
+                    time: toggle(args, "--time", "--no-time", defaults.time),
+                    dhat: toggle(args, "--dhat", "--no-dhat", defaults.dhat),
+                    dhat_part: args.opt_value_from_str("--dhat-part")?,
+                    params: args.values_from_str("--param")?,
+                    ocr: toggle(args, "--ocr", "--no-ocr", defaults.ocr),
+                }
+            }
+            "status" => AppArguments::Status {
+                json: args.contains("--json"),
+            },
+            "completions" => AppArguments::Completions {
+                shell: args.free_from_str()?,
+            },
+            "help" => AppArguments::Help {
+                command: args.opt_free_from_str()?,
             },
             #[cfg(feature = "today")]
-            Some("today") => AppArguments::Today,
-            Some(x) => {
+            "today" => AppArguments::Today,
+            x => {
                 eprintln!("Unknown command: {x}");
-                process::exit(1);
-            }
-            None => {
-                eprintln!("No command specified.");
+                eprintln!("Run `cargo run -- help` to list the available commands.");
                 process::exit(1);
             }
         };
 
-        let remaining = args.finish();
-        if !remaining.is_empty() {
-            eprintln!("Warning: unknown argument(s): {remaining:?}.");
-        }
-
         Ok(app_args)
     }
+
+    /// Resolves a boolean flag that can be enabled or disabled on the command-line, falling back to the configured default.
+    fn toggle(
+        args: &mut pico_args::Arguments,
+        enable: &'static str,
+        disable: &'static str,
+        default: bool,
+    ) -> bool {
+        let enabled = args.contains(enable);
+        let disabled = args.contains(disable);
+        enabled || (default && !disabled)
+    }
 }
 
 fn main() {
@@ -116,8 +211,22 @@ fn main() {
                 release,
                 time,
                 dhat,
+                dhat_part,
                 submit,
-            } => solve::handle(day, release, time, dhat, submit),
+                params,
+                ocr,
+            } => {
+                let run_args = RunArguments {
+                    time,
+                    submit,
+                    params,
+                    ocr,
+                };
+                solve::handle(day, release, dhat, dhat_part, run_args);
+            }
+            AppArguments::Status { json } => status::handle(json),
+            AppArguments::Completions { shell } => completions::handle(shell),
+            AppArguments::Help { command } => help::handle(command),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
@@ -0,0 +1,472 @@
+//! Exact integer maths: greatest common divisors, the chinese remainder theorem, integer square roots
+//! and the integer solutions of quadratic inequalities, e.g. the button press times of day 6.
+//!
+//! Functions panic when an intermediate result overflows, their `checked_` variants report it instead.
+use std::fmt::{Debug, Display};
+use std::ops::{Div, Rem};
+
+/// An intermediate result did not fit into its integer type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("arithmetic overflow")
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// Integer types supported by [`gcd`] and [`lcm`].
+pub trait Integer: Copy + Ord + Debug + Rem<Output = Self> + Div<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    /// The absolute value. Panics for the minimum of a signed type.
+    fn abs(self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($abs:ident; $( $type:ty ),*) => {
+        $(
+            impl Integer for $type {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$type>::checked_mul(self, other)
+                }
+
+                fn abs(self) -> Self {
+                    impl_integer!(@$abs self)
+                }
+            }
+        )*
+    };
+    (@unsigned $value:ident) => { $value };
+    (@signed $value:ident) => { $value.abs() };
+}
+
+impl_integer!(unsigned; u8, u16, u32, u64, u128, usize);
+impl_integer!(signed; i8, i16, i32, i64, i128, isize);
+
+/* -------------------------------------------------------------------------- */
+
+/// The greatest common divisor, which is never negative. `gcd(0, 0)` is 0.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+/// The least common multiple, which is never negative. It is 0 if either number is 0.
+///
+/// Panics if the result overflows.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("lcm overflows")
+}
+
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a / gcd(a, b)).checked_mul(b).map(Integer::abs)
+}
+
+/// The greatest common divisor of all numbers, 0 if there are none.
+pub fn gcd_all<T: Integer>(numbers: impl IntoIterator<Item = T>) -> T {
+    numbers.into_iter().fold(T::ZERO, gcd)
+}
+
+/// The least common multiple of all numbers, 1 if there are none, e.g. the step at which cycles of
+/// different lengths line up again.
+///
+/// Panics if the result overflows.
+pub fn lcm_all<T: Integer>(numbers: impl IntoIterator<Item = T>) -> T {
+    checked_lcm_all(numbers).expect("lcm overflows")
+}
+
+pub fn checked_lcm_all<T: Integer>(numbers: impl IntoIterator<Item = T>) -> Option<T> {
+    numbers
+        .into_iter()
+        .try_fold(T::ONE, |acc, n| checked_lcm(acc, n))
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y = g`, where `g` is the greatest common divisor of `a` and `b`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `a * b mod m` for `a, b < m`, without overflowing for any `m`.
+fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+
+    // double and add, keeping every intermediate value below `m`.
+    let (mut a, mut b, mut result) = (a, b, 0u128);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    result
+}
+
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Solves a system of congruences `x = residue (mod modulus)`, where the moduli need not be coprime.
+/// Returns the smallest non-negative solution and the modulus it repeats with, or `None` if the
+/// congruences contradict each other.
+///
+/// Panics if a modulus is not positive or the combined modulus overflows.
+///
+/// ```
+/// # use advent_of_code::math::crt;
+/// assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+/// assert_eq!(crt([(1, 4), (3, 6)]), Some((9, 12)));
+/// assert_eq!(crt([(1, 4), (2, 6)]), None);
+/// ```
+pub fn crt(congruences: impl IntoIterator<Item = (i128, i128)>) -> Option<(i128, i128)> {
+    checked_crt(congruences).expect("crt overflows")
+}
+
+pub fn checked_crt(
+    congruences: impl IntoIterator<Item = (i128, i128)>,
+) -> Result<Option<(i128, i128)>, Overflow> {
+    let (mut x, mut m) = (0i128, 1i128);
+
+    for (residue, modulus) in congruences {
+        assert!(modulus > 0, "modulus {modulus} is not positive");
+        let residue = residue.rem_euclid(modulus);
+
+        // x + m * k = residue (mod modulus) is solvable iff g divides the difference.
+        let (g, inverse, _) = extended_gcd(m, modulus);
+        let difference = residue - x;
+        if difference % g != 0 {
+            return Ok(None);
+        }
+
+        let step = modulus / g;
+        let k = mul_mod(
+            (difference / g).rem_euclid(step) as u128,
+            inverse.rem_euclid(step) as u128,
+            step as u128,
+        ) as i128;
+
+        let combined = m.checked_mul(step).ok_or(Overflow)?;
+        // x < m and k < step, so x + m * k < combined.
+        x += m * k;
+        m = combined;
+    }
+
+    Ok(Some((x, m)))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The integer square root, i.e. the largest `r` with `r * r <= n`.
+pub fn isqrt(n: u64) -> u64 {
+    isqrt_u128(n as u128) as u64
+}
+
+pub fn isqrt_u128(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    // the floating point estimate is close, the corrections make it exact.
+    let mut r = (n as f64).sqrt() as u128;
+    while r.checked_mul(r).is_none_or(|square| square > n) {
+        r -= 1;
+    }
+    while (r + 1).checked_mul(r + 1).is_some_and(|square| square <= n) {
+        r += 1;
+    }
+    r
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The range of integers `x` with `a * x^2 + b * x + c < 0`, as inclusive bounds, or `None` if there are none.
+/// Since the parabola has to open upwards, `a` must be positive.
+///
+/// Panics if `a` is not positive or an intermediate result overflows.
+///
+/// ```
+/// # use advent_of_code::math::quadratic_below_zero;
+/// // holding the button for t ms of a 7 ms race beats 9 mm if t * (7 - t) > 9.
+/// assert_eq!(quadratic_below_zero(1, -7, 9), Some((2, 5)));
+/// ```
+pub fn quadratic_below_zero(a: i128, b: i128, c: i128) -> Option<(i128, i128)> {
+    checked_quadratic_below_zero(a, b, c).expect("quadratic bounds overflow")
+}
+
+pub fn checked_quadratic_below_zero(
+    a: i128,
+    b: i128,
+    c: i128,
+) -> Result<Option<(i128, i128)>, Overflow> {
+    quadratic_bounds(a, b, c, |value| value < 0)
+}
+
+/// Like [`quadratic_below_zero`], but for `a * x^2 + b * x + c <= 0`.
+pub fn quadratic_at_most_zero(a: i128, b: i128, c: i128) -> Option<(i128, i128)> {
+    checked_quadratic_at_most_zero(a, b, c).expect("quadratic bounds overflow")
+}
+
+pub fn checked_quadratic_at_most_zero(
+    a: i128,
+    b: i128,
+    c: i128,
+) -> Result<Option<(i128, i128)>, Overflow> {
+    quadratic_bounds(a, b, c, |value| value <= 0)
+}
+
+/// The number of integers in inclusive bounds as returned by [`quadratic_below_zero`].
+pub fn bounds_len(bounds: Option<(i128, i128)>) -> u128 {
+    bounds.map_or(0, |(low, high)| high.abs_diff(low) + 1)
+}
+
+fn quadratic_bounds(
+    a: i128,
+    b: i128,
+    c: i128,
+    holds: impl Fn(i128) -> bool,
+) -> Result<Option<(i128, i128)>, Overflow> {
+    assert!(a > 0, "leading coefficient {a} is not positive");
+
+    let evaluate = |x: i128| -> Result<i128, Overflow> {
+        a.checked_mul(x)
+            .and_then(|ax| ax.checked_add(b))
+            .and_then(|axb| axb.checked_mul(x))
+            .and_then(|axbx| axbx.checked_add(c))
+            .ok_or(Overflow)
+    };
+
+    let discriminant = b
+        .checked_mul(b)
+        .zip(a.checked_mul(4).and_then(|a4| a4.checked_mul(c)))
+        .and_then(|(bb, ac4)| bb.checked_sub(ac4))
+        .ok_or(Overflow)?;
+    if discriminant < 0 {
+        return Ok(None);
+    }
+
+    // the roots are (-b ± sqrt(d)) / 2a. Rounding the square root and the division may be off by one,
+    // so the candidates around the vertex are corrected by evaluating the polynomial exactly.
+    let root = isqrt_u128(discriminant as u128) as i128;
+    let denominator = a.checked_mul(2).ok_or(Overflow)?;
+    let minus_b = b.checked_neg().ok_or(Overflow)?;
+    let vertex = minus_b.div_euclid(denominator);
+
+    if !holds(evaluate(vertex)?) && !holds(evaluate(vertex + 1)?) {
+        return Ok(None);
+    }
+    let inside = if holds(evaluate(vertex)?) {
+        vertex
+    } else {
+        vertex + 1
+    };
+
+    let mut low = (minus_b - root).div_euclid(denominator).min(inside);
+    while !holds(evaluate(low)?) {
+        low += 1;
+    }
+    while holds(evaluate(low - 1)?) {
+        low -= 1;
+    }
+
+    let mut high = minus_b
+        .checked_add(root)
+        .ok_or(Overflow)?
+        .div_euclid(denominator)
+        .max(inside);
+    while !holds(evaluate(high)?) {
+        high -= 1;
+    }
+    while holds(evaluate(high + 1)?) {
+        high += 1;
+    }
+
+    Ok(Some((low, high)))
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        bounds_len, checked_crt, checked_lcm, checked_lcm_all, checked_quadratic_below_zero, crt,
+        extended_gcd, gcd, gcd_all, isqrt, isqrt_u128, lcm, lcm_all, quadratic_at_most_zero,
+        quadratic_below_zero, Overflow,
+    };
+
+    #[test]
+    fn computes_gcd_and_lcm() {
+        assert_eq!(gcd(12u64, 18), 6);
+        assert_eq!(gcd(-12i64, 18), 6);
+        assert_eq!(gcd(0u32, 7), 7);
+        assert_eq!(gcd(0u32, 0), 0);
+        assert_eq!(lcm(4u64, 6), 12);
+        assert_eq!(lcm(-4i32, 6), 12);
+        assert_eq!(lcm(0u8, 5), 0);
+
+        assert_eq!(gcd_all([12u64, 18, 27]), 3);
+        assert_eq!(gcd_all(Vec::<u64>::new()), 0);
+        assert_eq!(lcm_all([2u64, 3, 4, 5]), 60);
+        assert_eq!(lcm_all(Vec::<u64>::new()), 1);
+
+        assert_eq!(checked_lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(checked_lcm(u64::MAX, 1), Some(u64::MAX));
+        assert_eq!(checked_lcm_all([1u8 << 7, 3]), None);
+    }
+
+    #[test]
+    fn computes_bezout_coefficients() {
+        for (a, b) in [
+            (240, 46),
+            (-240, 46),
+            (0, 5),
+            (7, 0),
+            (17, 31),
+            (i64::MAX as i128, 3),
+        ] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g, "{a}, {b}");
+        }
+    }
+
+    #[test]
+    fn solves_congruences() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(-1, 5)]), Some((4, 5)));
+        assert_eq!(crt([]), Some((0, 1)));
+
+        // non-coprime moduli, e.g. cycles that share a factor.
+        assert_eq!(crt([(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt([(0, 6), (0, 10), (0, 15)]), Some((0, 30)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+
+        // brute force over small moduli.
+        for m1 in 1..15 {
+            for m2 in 1..15 {
+                for (r1, r2) in [(0, 0), (1 % m1, 2 % m2), (m1 - 1, m2 / 2)] {
+                    let expected = (0..m1 * m2).find(|x| x % m1 == r1 && x % m2 == r2);
+                    let solution = crt([(r1, m1), (r2, m2)]);
+                    assert_eq!(
+                        solution.map(|(x, _)| x),
+                        expected,
+                        "{r1} mod {m1}, {r2} mod {m2}"
+                    );
+                    if let Some((_, m)) = solution {
+                        assert_eq!(m, lcm(m1, m2));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn handles_large_moduli() {
+        // the intermediate products exceed 128 bits.
+        let (p, q) = (3, (1 << 100) + 1);
+        let (x, m) = crt([(1, p), (5, q)]).unwrap();
+        assert_eq!((x % p, x % q, m), (1, 5, p * q));
+
+        let mersenne = (1 << 61) - 1;
+        assert_eq!(checked_crt([(0, q), (0, mersenne)]), Err(Overflow));
+    }
+
+    #[test]
+    fn computes_integer_square_roots() {
+        for n in 0..10_000u64 {
+            let r = isqrt(n);
+            assert!(r * r <= n && (r + 1) * (r + 1) > n, "{n}");
+        }
+        for r in [1u64 << 31, u32::MAX as u64, 3_037_000_499] {
+            assert_eq!(isqrt(r * r), r);
+            assert_eq!(isqrt(r * r - 1), r - 1);
+        }
+        assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+        assert_eq!(isqrt_u128(u128::MAX), u64::MAX as u128);
+        assert_eq!(
+            isqrt_u128((u64::MAX as u128) * (u64::MAX as u128) - 1),
+            u64::MAX as u128 - 1
+        );
+    }
+
+    #[test]
+    fn bounds_quadratic_inequalities() {
+        // t * (30 - t) > 200 from the day 6 example, where both roots are integers.
+        assert_eq!(quadratic_below_zero(1, -30, 200), Some((11, 19)));
+        assert_eq!(quadratic_at_most_zero(1, -30, 200), Some((10, 20)));
+        assert_eq!(bounds_len(quadratic_below_zero(1, -15, 40)), 8);
+        assert_eq!(bounds_len(quadratic_below_zero(1, -71530, 940200)), 71503);
+
+        // the vertex touches zero.
+        assert_eq!(quadratic_below_zero(1, -4, 4), None);
+        assert_eq!(quadratic_at_most_zero(1, -4, 4), Some((2, 2)));
+        assert_eq!(quadratic_below_zero(1, 0, 1), None);
+        assert_eq!(quadratic_below_zero(4, 0, -1), Some((0, 0)));
+
+        // brute force over small coefficients.
+        for a in 1..5 {
+            for b in -20..20 {
+                for c in -20..20 {
+                    let below: Vec<i128> =
+                        (-50..50).filter(|x| a * x * x + b * x + c < 0).collect();
+                    let expected = below.first().map(|low| (*low, *below.last().unwrap()));
+                    assert_eq!(
+                        quadratic_below_zero(a, b, c),
+                        expected,
+                        "{a}x^2 + {b}x + {c}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reports_overflowing_quadratics() {
+        assert_eq!(checked_quadratic_below_zero(1, i128::MAX, 0), Err(Overflow));
+
+        // a race of 2^62 ms, where floating point roots are not precise enough.
+        let time = 1i128 << 62;
+        let distance = (time / 2) * (time / 2) - 1;
+        assert_eq!(
+            quadratic_below_zero(1, -time, distance),
+            Some((time / 2, time / 2))
+        );
+    }
+}
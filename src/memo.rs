@@ -0,0 +1,241 @@
+//! Memoization for recursive counting solutions, e.g. the arrangements of day 12.
+//!
+//! Every lookup is also counted globally, so that `cargo solve --time` can report the hit rate of
+//! all caches used by a part.
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+/// Counts of cache lookups that found a value (hits) and that had to compute one (misses).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl Stats {
+    pub fn lookups(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    /// The share of lookups that were hits, between 0 and 1.
+    pub fn hit_rate(&self) -> f64 {
+        if self.lookups() == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.lookups() as f64
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate)",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0
+        )
+    }
+}
+
+/// The lookups of all caches since the last call, used by the runner to report them per part.
+pub fn take_global_stats() -> Stats {
+    Stats {
+        hits: HITS.swap(0, Ordering::Relaxed),
+        misses: MISSES.swap(0, Ordering::Relaxed),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A cache of computed values. Recursive functions pass it along and look values up with
+/// [`Memo::get_or_insert_with`], which hands the cache back to the computation.
+///
+/// ```
+/// # use advent_of_code::memo::Memo;
+/// fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+///     memo.get_or_insert_with(n, |memo| match n {
+///         0 | 1 => n,
+///         _ => fibonacci(memo, n - 1) + fibonacci(memo, n - 2),
+///     })
+/// }
+/// assert_eq!(fibonacci(&mut Memo::new(), 90), 2880067194370816120);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    stats: Stats,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+            stats: Stats::default(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The cached value of `key`, or the value computed by `f`, which is cached afterwards.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            HITS.fetch_add(1, Ordering::Relaxed);
+            return value.clone();
+        }
+
+        self.stats.misses += 1;
+        MISSES.fetch_add(1, Ordering::Relaxed);
+
+        // the key is only inserted afterwards, as `f` may add other keys first.
+        let value = f(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// The number of cached values.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// The lookups of this cache.
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Removes all values, e.g. between lines of an input whose results do not carry over.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Wraps a recursive function in a cache. Instead of calling itself, `f` calls the function it
+/// receives as first argument, which looks the value up first.
+///
+/// ```
+/// # use advent_of_code::memo::memoize;
+/// let mut paths = memoize(|paths, (x, y): (u64, u64)| match (x, y) {
+///     (0, _) | (_, 0) => 1,
+///     _ => paths((x - 1, y)) + paths((x, y - 1)),
+/// });
+/// assert_eq!(paths.call((16, 16)), 601080390);
+/// ```
+pub fn memoize<K, V, F>(f: F) -> Memoized<K, V, F>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    Memoized {
+        memo: Memo::new(),
+        f,
+    }
+}
+
+pub struct Memoized<K, V, F> {
+    memo: Memo<K, V>,
+    f: F,
+}
+
+impl<K, V, F> Memoized<K, V, F>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    pub fn call(&mut self, key: K) -> V {
+        call_memoized(&self.f, &mut self.memo, key)
+    }
+
+    /// The cache, e.g. to inspect its statistics or clear it.
+    pub fn memo(&mut self) -> &mut Memo<K, V> {
+        &mut self.memo
+    }
+}
+
+fn call_memoized<K, V, F>(f: &F, memo: &mut Memo<K, V>, key: K) -> V
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    memo.get_or_insert_with(key.clone(), |memo| {
+        f(&mut |next| call_memoized(f, memo, next), key)
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{memoize, Memo, Stats};
+
+    fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+        memo.get_or_insert_with(n, |memo| match n {
+            0 | 1 => n,
+            _ => fibonacci(memo, n - 1) + fibonacci(memo, n - 2),
+        })
+    }
+
+    #[test]
+    fn caches_recursive_functions() {
+        let mut memo = Memo::new();
+        assert_eq!(fibonacci(&mut memo, 90), 2880067194370816120);
+        assert_eq!(memo.len(), 91);
+        // every n above 1 looks up n - 1 (a miss) and n - 2 (a hit).
+        assert_eq!(
+            memo.stats(),
+            Stats {
+                hits: 88,
+                misses: 91
+            }
+        );
+
+        assert_eq!(fibonacci(&mut memo, 50), 12586269025);
+        assert_eq!(memo.stats().hits, 89);
+
+        memo.clear();
+        assert!(memo.is_empty());
+    }
+
+    #[test]
+    fn memoizes_closures() {
+        let mut paths = memoize(|paths, (x, y): (u64, u64)| match (x, y) {
+            (0, _) | (_, 0) => 1,
+            _ => paths((x - 1, y)) + paths((x, y - 1)),
+        });
+
+        assert_eq!(paths.call((16, 16)), 601080390);
+        assert_eq!(paths.memo().len(), 17 * 17 - 1);
+        assert_eq!(paths.call((2, 3)), 10);
+        assert_eq!(paths.memo().get(&(1, 1)), Some(&2));
+    }
+
+    #[test]
+    fn formats_stats() {
+        let stats = Stats { hits: 3, misses: 1 };
+        assert_eq!(stats.to_string(), "3 hits, 1 misses (75.0% hit rate)");
+        assert_eq!(Stats::default().hit_rate(), 0.0);
+    }
+}
@@ -0,0 +1,461 @@
+//! Helpers to parse puzzle inputs without compiling regular expressions.
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+/// The lines of an input. `\r\n` line endings and trailing newlines are ignored.
+pub fn lines(s: &str) -> impl Iterator<Item = &str> {
+    s.trim_end_matches(['\r', '\n']).lines()
+}
+
+/// The blocks of an input that are separated by one or more blank lines, e.g. the maps of day 5.
+/// Blocks do not include their trailing newline.
+pub fn blocks(s: &str) -> Blocks<'_> {
+    Blocks { rest: s }
+}
+
+pub struct Blocks<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Blocks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        // skip blank lines before the block.
+        let rest = self.rest.trim_start_matches(['\r', '\n']);
+        if rest.is_empty() {
+            self.rest = rest;
+            return None;
+        }
+
+        let mut end = rest.len();
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            if line.trim_end_matches(['\r', '\n']).is_empty() {
+                end = offset;
+                break;
+            }
+            offset += line.len();
+        }
+
+        self.rest = &rest[end..];
+        Some(rest[..end].trim_end_matches(['\r', '\n']))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The unsigned integers in a string, e.g. `[12, 5]` for `"Card 12: 5"`. Signs are ignored.
+///
+/// A number that does not fit into `T` yields an error.
+pub fn unsigned<T: FromStr>(s: &str) -> Integers<'_, T> {
+    Integers {
+        input: s,
+        offset: 0,
+        signed: false,
+        marker: PhantomData,
+    }
+}
+
+/// The signed integers in a string, e.g. `[10, -3]` for `"10 -3"`. A `-` directly before digits negates them.
+///
+/// A number that does not fit into `T` yields an error.
+pub fn signed<T: FromStr>(s: &str) -> Integers<'_, T> {
+    Integers {
+        input: s,
+        offset: 0,
+        signed: true,
+        marker: PhantomData,
+    }
+}
+
+pub struct Integers<'a, T> {
+    input: &'a str,
+    /// The byte offset of the rest of the input that has not been searched yet.
+    offset: usize,
+    signed: bool,
+    marker: PhantomData<T>,
+}
+
+impl<T: FromStr> Iterator for Integers<'_, T> {
+    type Item = Result<T, ScanError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.input.as_bytes();
+        let first_digit = self.offset + bytes[self.offset..].iter().position(u8::is_ascii_digit)?;
+        let end = bytes[first_digit..]
+            .iter()
+            .position(|b| !b.is_ascii_digit())
+            .map_or(bytes.len(), |len| first_digit + len);
+
+        let start = if self.signed && first_digit > 0 && bytes[first_digit - 1] == b'-' {
+            first_digit - 1
+        } else {
+            first_digit
+        };
+
+        let number = &self.input[start..end];
+        self.offset = end;
+
+        Some(number.parse().map_err(|_| ScanError {
+            offset: start,
+            message: format!(
+                "integer `{number}` does not fit into {}.",
+                std::any::type_name::<T>()
+            ),
+        }))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An error of [`scan`], [`unsigned`] or [`signed`], positioned at a byte offset of the input.
+#[derive(Debug, PartialEq)]
+pub struct ScanError {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at column {}: {}", self.offset + 1, self.message)
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+/// A value that can be extracted by [`scan`]. Implemented for `&str`, `String`, `char`, `bool` and the number types.
+pub trait Capture<'a>: Sized {
+    fn from_capture(s: &'a str) -> Result<Self, String>;
+}
+
+impl<'a> Capture<'a> for &'a str {
+    fn from_capture(s: &'a str) -> Result<Self, String> {
+        Ok(s)
+    }
+}
+
+macro_rules! impl_capture_from_str {
+    ($( $type:ty ),*) => {
+        $(
+            impl Capture<'_> for $type {
+                fn from_capture(s: &str) -> Result<Self, String> {
+                    s.parse().map_err(|e| format!("invalid value `{s}`: {e}"))
+                }
+            }
+        )*
+    };
+}
+
+impl_capture_from_str!(
+    String, char, bool, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64
+);
+
+/// A tuple of [`Capture`]s, one for each `{}` of a pattern.
+pub trait Captures<'a>: Sized {
+    const LEN: usize;
+
+    fn from_captures(captures: &[(usize, &'a str)]) -> Result<Self, ScanError>;
+}
+
+macro_rules! impl_captures {
+    ($len:expr; $( $name:ident $idx:tt ),*) => {
+        impl<'a, $( $name: Capture<'a> ),*> Captures<'a> for ($( $name, )*) {
+            const LEN: usize = $len;
+
+            fn from_captures(captures: &[(usize, &'a str)]) -> Result<Self, ScanError> {
+                Ok(($(
+                    $name::from_capture(captures[$idx].1).map_err(|message| ScanError {
+                        offset: captures[$idx].0,
+                        message,
+                    })?,
+                )*))
+            }
+        }
+    };
+}
+
+impl_captures!(1; A 0);
+impl_captures!(2; A 0, B 1);
+impl_captures!(3; A 0, B 1, C 2);
+impl_captures!(4; A 0, B 1, C 2, D 3);
+impl_captures!(5; A 0, B 1, C 2, D 3, E 4);
+impl_captures!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+
+/// The largest number of `{}` in a pattern, i.e. the largest [`Captures`] tuple.
+const MAX_CAPTURES: usize = 6;
+
+/// A piece of a pattern: literal text or a `{}` placeholder.
+#[derive(Clone, Copy)]
+enum Token<'p> {
+    Literal(&'p str),
+    Capture,
+}
+
+/// The tokens of a pattern, borrowed from it. An escaped brace is a literal of its own.
+#[derive(Clone)]
+struct Tokens<'p> {
+    rest: &'p str,
+}
+
+impl<'p> Iterator for Tokens<'p> {
+    type Item = Token<'p>;
+
+    fn next(&mut self) -> Option<Token<'p>> {
+        let starts_token = |s: &str| ["{}", "{{", "}}"].iter().any(|t| s.starts_with(t));
+        let rest = self.rest;
+
+        let (token, len) = if rest.is_empty() {
+            return None;
+        } else if rest.starts_with("{}") {
+            (Token::Capture, 2)
+        } else if starts_token(rest) {
+            (Token::Literal(&rest[..1]), 2)
+        } else {
+            let len = rest
+                .char_indices()
+                .skip(1)
+                .find(|(idx, _)| starts_token(&rest[*idx..]))
+                .map_or(rest.len(), |(idx, _)| idx);
+            (Token::Literal(&rest[..len]), len)
+        };
+
+        self.rest = &rest[len..];
+        Some(token)
+    }
+}
+
+fn tokenize(pattern: &str) -> Tokens<'_> {
+    Tokens { rest: pattern }
+}
+
+/// The first position of `s` that is followed by the literals at the start of `tokens`.
+/// `None` if there are no literals, or they do not follow each other anywhere.
+fn find_literals(s: &str, tokens: &Tokens<'_>) -> Option<usize> {
+    let literals = || {
+        tokens.clone().map_while(|token| match token {
+            Token::Literal(literal) => Some(literal),
+            Token::Capture => None,
+        })
+    };
+    let first = literals().next()?;
+
+    let mut from = 0;
+    loop {
+        let idx = from + s[from..].find(first)?;
+        let mut rest = &s[idx..];
+        if literals().all(|literal| match rest.strip_prefix(literal) {
+            Some(after) => {
+                rest = after;
+                true
+            }
+            None => false,
+        }) {
+            return Some(idx);
+        }
+        from = idx + s[idx..].chars().next().map_or(1, char::len_utf8);
+    }
+}
+
+fn preview(s: &str) -> String {
+    if s.is_empty() {
+        "end of input".into()
+    } else {
+        format!("`{}`", s.chars().take(10).collect::<String>())
+    }
+}
+
+/// Extracts values from `input` according to a pattern, where `{}` captures text up to the
+/// following literal part of the pattern (or the end of input). `{{` and `}}` match literal braces.
+///
+/// ```
+/// # use advent_of_code::parse::scan;
+/// let (id, rest): (u32, &str) = scan("Game {}: {}", "Game 7: 3 blue").unwrap();
+/// assert_eq!((id, rest), (7, "3 blue"));
+/// ```
+pub fn scan<'a, T: Captures<'a>>(pattern: &str, input: &'a str) -> Result<T, ScanError> {
+    let placeholders = tokenize(pattern)
+        .filter(|t| matches!(t, Token::Capture))
+        .count();
+
+    if placeholders != T::LEN {
+        return Err(ScanError {
+            offset: 0,
+            message: format!(
+                "pattern `{pattern}` has {placeholders} placeholders, expected {}.",
+                T::LEN
+            ),
+        });
+    }
+
+    let mut captures = [(0, ""); MAX_CAPTURES];
+    let mut captured = 0;
+    let mut offset = 0;
+    let mut tokens = tokenize(pattern);
+
+    while let Some(token) = tokens.next() {
+        let rest = &input[offset..];
+
+        match token {
+            Token::Literal(literal) => {
+                if !rest.starts_with(literal) {
+                    return Err(ScanError {
+                        offset,
+                        message: format!("expected `{literal}`, found {}.", preview(rest)),
+                    });
+                }
+                offset += literal.len();
+            }
+            Token::Capture => {
+                let len = match tokens.clone().next() {
+                    Some(Token::Literal(literal)) => {
+                        find_literals(rest, &tokens).ok_or_else(|| ScanError {
+                            offset: offset + rest.len(),
+                            message: format!("expected `{literal}`, found end of input."),
+                        })?
+                    }
+                    // a capture directly followed by another one would be ambiguous, it captures nothing.
+                    Some(Token::Capture) => 0,
+                    None => rest.len(),
+                };
+                captures[captured] = (offset, &rest[..len]);
+                captured += 1;
+                offset += len;
+            }
+        }
+    }
+
+    if offset < input.len() {
+        return Err(ScanError {
+            offset,
+            message: format!(
+                "expected end of input, found {}.",
+                preview(&input[offset..])
+            ),
+        });
+    }
+
+    T::from_captures(&captures[..captured])
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{blocks, lines, scan, signed, unsigned, ScanError};
+
+    #[test]
+    fn splits_lines() {
+        let v: Vec<&str> = lines("a\r\nb\n\n").collect();
+        assert_eq!(v, vec!["a", "b"]);
+        assert_eq!(lines("").count(), 0);
+        let v: Vec<&str> = lines("a\n\nb").collect();
+        assert_eq!(v, vec!["a", "", "b"]);
+    }
+
+    #[test]
+    fn splits_blocks() {
+        let v: Vec<&str> = blocks("seeds: 1\n\na:\n1 2\r\n\r\n\r\nb:\n3\n").collect();
+        assert_eq!(v, vec!["seeds: 1", "a:\n1 2", "b:\n3"]);
+        assert_eq!(blocks("\n\n").count(), 0);
+        let v: Vec<&str> = blocks("single").collect();
+        assert_eq!(v, vec!["single"]);
+    }
+
+    #[test]
+    fn extracts_unsigned_integers() {
+        let v: Vec<u32> = unsigned("Card  12: 41 48 | -83")
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(v, vec![12, 41, 48, 83]);
+        assert_eq!(unsigned::<u8>("none").count(), 0);
+        let v: Vec<u64> = unsigned("4294967296").map(Result::unwrap).collect();
+        assert_eq!(v, vec![4294967296]);
+    }
+
+    #[test]
+    fn extracts_signed_integers() {
+        let v: Vec<i64> = signed("10 -3 0 -0 x-7 5-2").map(Result::unwrap).collect();
+        assert_eq!(v, vec![10, -3, 0, 0, -7, 5, -2]);
+    }
+
+    #[test]
+    fn reports_overflowing_integers() {
+        let v: Vec<Result<u8, ScanError>> = unsigned("1 256 -3").collect();
+        assert_eq!(
+            v,
+            vec![
+                Ok(1),
+                Err(ScanError {
+                    offset: 2,
+                    message: "integer `256` does not fit into u8.".into()
+                }),
+                Ok(3)
+            ]
+        );
+        let err = signed::<i8>("x -129")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "at column 3: integer `-129` does not fit into i8."
+        );
+    }
+
+    #[test]
+    fn scans_typed_captures() {
+        let (id, rest): (u32, &str) = scan("Game {}: {}", "Game 7: 3 blue").unwrap();
+        assert_eq!((id, rest), (7, "3 blue"));
+
+        let (a, b, c): (u64, u64, u64) = scan("{} {} {}", "50 98 2").unwrap();
+        assert_eq!((a, b, c), (50, 98, 2));
+
+        let (name, left, right): (&str, String, &str) =
+            scan("{} = ({}, {})", "AAA = (BBB, CCC)").unwrap();
+        assert_eq!((name, left.as_str(), right), ("AAA", "BBB", "CCC"));
+
+        let (x,): (i32,) = scan("{{{}}}", "{-4}").unwrap();
+        assert_eq!(x, -4);
+    }
+
+    #[test]
+    fn reports_positioned_errors() {
+        let err = scan::<(u32, &str)>("Game {}: {}", "Gme 7: 3 blue").unwrap_err();
+        assert_eq!(err.offset, 0);
+        assert_eq!(
+            err.to_string(),
+            "at column 1: expected `Game `, found `Gme 7: 3 b`."
+        );
+
+        let err = scan::<(u32, &str)>("Game {}: {}", "Game x7: 3 blue").unwrap_err();
+        assert_eq!(err.offset, 5);
+        assert!(err.message.starts_with("invalid value `x7`"));
+
+        let err = scan::<(u32, u32)>("{} {}", "1").unwrap_err();
+        assert_eq!(
+            err,
+            ScanError {
+                offset: 1,
+                message: "expected ` `, found end of input.".into()
+            }
+        );
+
+        let err = scan::<(u32,)>("{}!", "1!?").unwrap_err();
+        assert_eq!(err.offset, 2);
+
+        assert!(scan::<(u32,)>("{} {}", "1 2").is_err());
+    }
+
+    #[test]
+    fn matches_escaped_braces_after_captures() {
+        // the capture ends before `}}` and `!` that follow each other, not at the first `}`.
+        let (a,): (&str,) = scan("{}}}!", "a}b}!").unwrap();
+        assert_eq!(a, "a}b");
+        let (a, b): (&str, &str) = scan("{}{{{}", "x{{y").unwrap();
+        assert_eq!((a, b), ("x", "{y"));
+        let (a,): (&str,) = scan("{}ab", "aab").unwrap();
+        assert_eq!(a, "a");
+        assert!(scan::<(&str,)>("{}}}!", "a}b}").is_err());
+    }
+}
@@ -0,0 +1,53 @@
+//! A small deterministic random number generator, e.g. to compare a solution against a slower
+//! reference implementation on generated inputs in tests.
+//!
+//! It is an xorshift generator: fast and reproducible from its seed, but not suitable for anything
+//! that needs unpredictable numbers.
+
+/// An xorshift generator. The same seed always yields the same numbers.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// A zero seed would only ever yield zeros, it is replaced by a fixed non-zero one.
+    pub fn new(seed: u64) -> Self {
+        Rng(if seed == 0 { 0x2545f4914f6cdd1d } else { seed })
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `0..n`. Panics if `n` is zero.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Rng;
+
+    #[test]
+    fn repeats_for_the_same_seed() {
+        let numbers = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..5).map(|_| rng.next_u64()).collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(7), numbers(7));
+        assert_ne!(numbers(7), numbers(8));
+        assert!(numbers(0).iter().all(|x| *x != 0));
+    }
+
+    #[test]
+    fn stays_below_bound() {
+        let mut rng = Rng::new(42);
+        assert!((0..1000).all(|_| rng.below(7) < 7));
+        assert_eq!(rng.below(1), 0);
+    }
+}
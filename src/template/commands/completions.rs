@@ -0,0 +1,394 @@
+use std::{error::Error, fmt::Display, path::Path, str::FromStr};
+
+use crate::template::{all_days, run_multi::get_path_for_bin, Day};
+
+use super::help::{CommandSpec, FlagSpec, COMMANDS, GLOBAL_FLAGS};
+
+/// Name of the binary completions are registered for.
+const BIN_NAME: &str = "advent_of_code";
+
+/// Commands that `.cargo/config.toml` defines as cargo aliases, e.g. `cargo solve`.
+/// Completions for `cargo` hand every other subcommand over to cargo's own completions.
+pub const CARGO_ALIASES: &[&str] = &[
+    "today", "scaffold", "download", "read", "solve", "all", "time", "status",
+];
+
+/// A shell that completion scripts can be generated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl FromStr for Shell {
+    type Err = ShellFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            _ => Err(ShellFromStrError),
+        }
+    }
+}
+
+/// An error which can be returned when parsing a [`Shell`].
+#[derive(Debug)]
+pub struct ShellFromStrError;
+
+impl Error for ShellFromStrError {}
+
+impl Display for ShellFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting one of `bash`, `zsh` or `fish`")
+    }
+}
+
+pub fn handle(shell: Shell) {
+    let scaffolded: Vec<Day> = all_days()
+        .filter(|day| Path::new(&get_path_for_bin(*day)).exists())
+        .collect();
+
+    println!("{}", script(shell, &scaffolded));
+}
+
+/// Generates the completion script for a shell.
+/// `scaffold` completes the days that have not been scaffolded yet, every other command that takes a day completes the scaffolded ones.
+pub fn script(shell: Shell, scaffolded: &[Day]) -> String {
+    match shell {
+        Shell::Bash => bash(scaffolded),
+        Shell::Zsh => zsh(scaffolded),
+        Shell::Fish => fish(scaffolded),
+    }
+}
+
+fn day_candidates(command: &CommandSpec, scaffolded: &[Day]) -> Vec<String> {
+    if !command.takes_day() {
+        return vec![];
+    }
+
+    all_days()
+        .filter(|day| (command.name == "scaffold") != scaffolded.contains(day))
+        .map(|day| day.to_string())
+        .collect()
+}
+
+fn value_candidates(value: &str) -> &'static str {
+    match value {
+        "part" => "1 2",
+        _ => "",
+    }
+}
+
+fn flags(command: &CommandSpec) -> impl Iterator<Item = &'static FlagSpec> {
+    command.flags.iter().chain(GLOBAL_FLAGS)
+}
+
+fn positional_candidates(command: &CommandSpec, scaffolded: &[Day]) -> Vec<String> {
+    match command.name {
+        "completions" => vec!["bash".into(), "zsh".into(), "fish".into()],
+        "help" => COMMANDS.iter().map(|c| c.name.to_string()).collect(),
+        _ => day_candidates(command, scaffolded),
+    }
+}
+
+fn bash(scaffolded: &[Day]) -> String {
+    let command_names: Vec<&str> = COMMANDS.iter().map(|c| c.name).collect();
+
+    let mut lines = vec![
+        format!("_{BIN_NAME}() {{"),
+        "    local cur prev words".into(),
+        "    cur=\"${COMP_WORDS[COMP_CWORD]}\"".into(),
+        "    prev=\"${COMP_WORDS[COMP_CWORD-1]}\"".into(),
+        String::new(),
+        "    if [ \"$COMP_CWORD\" -eq 1 ]; then".into(),
+        format!(
+            "        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+            command_names.join(" ")
+        ),
+        "        return".into(),
+        "    fi".into(),
+        String::new(),
+        "    case \"$prev\" in".into(),
+    ];
+
+    let mut value_flags: Vec<(&str, &str)> = COMMANDS
+        .iter()
+        .flat_map(|c| c.flags)
+        .chain(GLOBAL_FLAGS)
+        .filter_map(|f| f.value.map(|value| (f.name, value)))
+        .collect();
+    value_flags.sort_unstable();
+    value_flags.dedup();
+
+    for (flag, value) in value_flags {
+        let reply = match value {
+            "path" => "compgen -f -- \"$cur\"".to_string(),
+            value => format!("compgen -W \"{}\" -- \"$cur\"", value_candidates(value)),
+        };
+        lines.push(format!("        {flag}) COMPREPLY=($({reply})); return ;;"));
+    }
+
+    lines.push("    esac".into());
+    lines.push(String::new());
+    lines.push("    case \"${COMP_WORDS[1]}\" in".into());
+
+    for command in COMMANDS {
+        let mut words: Vec<String> = flags(command).map(|f| f.name.into()).collect();
+        words.extend(positional_candidates(command, scaffolded));
+        lines.push(format!(
+            "        {}) words=\"{}\" ;;",
+            command.name,
+            words.join(" ")
+        ));
+    }
+
+    lines.push("        *) words=\"\" ;;".into());
+    lines.push("    esac".into());
+    lines.push(String::new());
+    lines.push("    COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))".into());
+    lines.push("}".into());
+    lines.push(String::new());
+    lines.push(format!("complete -F _{BIN_NAME} {BIN_NAME}"));
+    lines.push(String::new());
+
+    // `cargo solve 1` has the same shape as `advent_of_code solve 1`.
+    lines.extend([
+        format!("_{BIN_NAME}_cargo() {{"),
+        "    if [ \"$COMP_CWORD\" -ge 2 ]; then".into(),
+        "        case \"${COMP_WORDS[1]}\" in".into(),
+        format!("            {}) _{BIN_NAME}; return ;;", CARGO_ALIASES.join("|")),
+        "        esac".into(),
+        "    fi".into(),
+        String::new(),
+        "    # bash-completion loads cargo's completions lazily, which registers them for `cargo` again.".into(),
+        "    if ! declare -F _cargo >/dev/null && declare -F _completion_loader >/dev/null; then".into(),
+        "        _completion_loader cargo".into(),
+        format!("        complete -F _{BIN_NAME}_cargo cargo"),
+        "    fi".into(),
+        "    if declare -F _cargo >/dev/null; then".into(),
+        "        _cargo \"$@\"".into(),
+        "    fi".into(),
+        "}".into(),
+        String::new(),
+        format!("complete -F _{BIN_NAME}_cargo cargo"),
+    ]);
+
+    lines.join("\n")
+}
+
+fn zsh(scaffolded: &[Day]) -> String {
+    let mut lines = vec![
+        format!("#compdef {BIN_NAME} cargo"),
+        String::new(),
+        format!("_{BIN_NAME}_command() {{"),
+        "    local -a commands".into(),
+        "    commands=(".into(),
+    ];
+
+    for command in COMMANDS {
+        lines.push(format!(
+            "        '{}:{}'",
+            command.name,
+            escape_zsh(command.description)
+        ));
+    }
+
+    lines.push("    )".into());
+    lines.push(String::new());
+    lines.push("    if (( CURRENT == 2 )); then".into());
+    lines.push("        _describe 'command' commands".into());
+    lines.push("        return".into());
+    lines.push("    fi".into());
+    lines.push(String::new());
+    // `_arguments` expects the subcommand in place of the command name.
+    lines.push("    local command=$words[2]".into());
+    lines.push("    shift words".into());
+    lines.push("    (( CURRENT-- ))".into());
+    lines.push(String::new());
+    lines.push("    case $command in".into());
+
+    for command in COMMANDS {
+        let mut specs: Vec<String> = flags(command)
+            .map(|f| match f.value {
+                Some("path") => format!("'{}[{}]:path:_files'", f.name, escape_zsh(f.description)),
+                Some(value) => format!(
+                    "'{}[{}]:{value}:({})'",
+                    f.name,
+                    escape_zsh(f.description),
+                    value_candidates(value)
+                ),
+                None => format!("'{}[{}]'", f.name, escape_zsh(f.description)),
+            })
+            .collect();
+
+        let positional = positional_candidates(command, scaffolded);
+        if !positional.is_empty() {
+            let name = command.args.trim_matches(|c| "<>[]".contains(c));
+            specs.push(format!("'1:{name}:({})'", positional.join(" ")));
+        }
+
+        lines.push(format!(
+            "        {}) _arguments -s {} ;;",
+            command.name,
+            specs.join(" ")
+        ));
+    }
+
+    lines.push("    esac".into());
+    lines.push("}".into());
+    lines.push(String::new());
+
+    // `cargo solve 1` has the same shape as `advent_of_code solve 1`.
+    lines.extend([
+        format!("_{BIN_NAME}_cargo() {{"),
+        "    if (( CURRENT > 2 )); then".into(),
+        "        case $words[2] in".into(),
+        format!(
+            "            {}) _{BIN_NAME}_command; return ;;",
+            CARGO_ALIASES.join("|")
+        ),
+        "        esac".into(),
+        "    fi".into(),
+        "    (( $+functions[_cargo] )) && _cargo \"$@\"".into(),
+        "}".into(),
+        String::new(),
+        "if [[ $service == cargo ]]; then".into(),
+        format!("    _{BIN_NAME}_cargo \"$@\""),
+        "else".into(),
+        format!("    _{BIN_NAME}_command \"$@\""),
+        "fi".into(),
+    ]);
+
+    lines.join("\n")
+}
+
+fn fish(scaffolded: &[Day]) -> String {
+    let command_names: Vec<&str> = COMMANDS.iter().map(|c| c.name).collect();
+    let mut lines = vec![format!("complete -c {BIN_NAME} -f")];
+
+    for command in COMMANDS {
+        lines.push(format!(
+            "complete -c {BIN_NAME} -n 'not __fish_seen_subcommand_from {}' -a {} -d '{}'",
+            command_names.join(" "),
+            command.name,
+            escape_fish(command.description)
+        ));
+    }
+
+    for command in COMMANDS {
+        fish_command(&mut lines, BIN_NAME, command, scaffolded);
+    }
+
+    // cargo's own completions list the aliases, only their arguments are added.
+    for command in COMMANDS.iter().filter(|c| CARGO_ALIASES.contains(&c.name)) {
+        fish_command(&mut lines, "cargo", command, scaffolded);
+    }
+
+    lines.join("\n")
+}
+
+fn fish_command(lines: &mut Vec<String>, bin: &str, command: &CommandSpec, scaffolded: &[Day]) {
+    let condition = format!("__fish_seen_subcommand_from {}", command.name);
+
+    for flag in flags(command) {
+        let name = flag.name.trim_start_matches('-');
+        let values = match flag.value {
+            Some("path") => " -r -F".to_string(),
+            Some(value) => format!(" -x -a '{}'", value_candidates(value)),
+            None => String::new(),
+        };
+        lines.push(format!(
+            "complete -c {bin} -n '{condition}' -l {name}{values} -d '{}'",
+            escape_fish(flag.description)
+        ));
+    }
+
+    let positional = positional_candidates(command, scaffolded);
+    if !positional.is_empty() {
+        lines.push(format!(
+            "complete -c {bin} -n '{condition}' -a '{}'",
+            positional.join(" ")
+        ));
+    }
+}
+
+fn escape_zsh(s: &str) -> String {
+    s.replace('\'', "'\\''")
+        .replace(':', "\\:")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
+
+fn escape_fish(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{script, Shell, CARGO_ALIASES};
+    use crate::day;
+    use std::fs;
+
+    #[test]
+    fn parses_shells() {
+        assert_eq!("bash".parse::<Shell>().unwrap(), Shell::Bash);
+        assert_eq!("zsh".parse::<Shell>().unwrap(), Shell::Zsh);
+        assert_eq!("fish".parse::<Shell>().unwrap(), Shell::Fish);
+        assert!("powershell".parse::<Shell>().is_err());
+    }
+
+    #[test]
+    fn completes_scaffolded_days() {
+        let s = script(Shell::Bash, &[day!(1), day!(3)]);
+        let solve = s.lines().find(|l| l.trim().starts_with("solve)")).unwrap();
+        assert!(solve.contains(" 01 03\""));
+        assert!(!solve.contains("02"));
+    }
+
+    #[test]
+    fn completes_unscaffolded_days_for_scaffold() {
+        let s = script(Shell::Fish, &[day!(1)]);
+        let scaffold = s.lines().find(|l| l.contains("from scaffold' -a")).unwrap();
+        assert!(scaffold.contains("'02 03"));
+        assert!(!scaffold.contains("01"));
+    }
+
+    #[test]
+    fn completes_submit_parts() {
+        let s = script(Shell::Zsh, &[]);
+        assert!(s.contains(
+            "'--submit[Submit the answer of the given part (1 or 2) via aoc-cli.]:part:(1 2)'"
+        ));
+    }
+
+    #[test]
+    fn matches_cargo_aliases() {
+        let config = fs::read_to_string(".cargo/config.toml").unwrap();
+        let mut aliases: Vec<&str> = config
+            .lines()
+            .filter_map(|line| line.split_once(" = \"run "))
+            .map(|(alias, _)| alias.trim())
+            .collect();
+        aliases.sort_unstable();
+        let mut expected = CARGO_ALIASES.to_vec();
+        expected.sort_unstable();
+        assert_eq!(aliases, expected);
+    }
+
+    #[test]
+    fn completes_cargo_aliases() {
+        let s = script(Shell::Bash, &[day!(1)]);
+        assert!(s.contains("solve|all|time|status) _advent_of_code; return ;;"));
+        assert!(s.ends_with("complete -F _advent_of_code_cargo cargo"));
+
+        let s = script(Shell::Zsh, &[day!(1)]);
+        assert!(s.starts_with("#compdef advent_of_code cargo\n"));
+
+        let s = script(Shell::Fish, &[day!(1)]);
+        assert!(s.contains("complete -c cargo -n '__fish_seen_subcommand_from solve' -a '01'"));
+        assert!(!s.contains("complete -c cargo -n '__fish_seen_subcommand_from help'"));
+    }
+}
@@ -0,0 +1,326 @@
+use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+/// Describes a single flag accepted by a command.
+pub struct FlagSpec {
+    pub name: &'static str,
+    pub value: Option<&'static str>,
+    pub description: &'static str,
+}
+
+/// Describes a command of the CLI. Used to render help texts and shell completions.
+pub struct CommandSpec {
+    pub name: &'static str,
+    /// Positional arguments in usage notation, e.g. `<day>` or `[day]`.
+    pub args: &'static str,
+    pub description: &'static str,
+    pub flags: &'static [FlagSpec],
+}
+
+impl CommandSpec {
+    /// Whether the command accepts a day number as positional argument.
+    pub fn takes_day(&self) -> bool {
+        self.args.contains("day")
+    }
+
+    /// Formats the usage line for this command, e.g. `solve <day> [--release] [--submit <part>]`.
+    pub fn usage(&self) -> String {
+        let mut usage = self.name.to_string();
+
+        if !self.args.is_empty() {
+            usage.push(' ');
+            usage.push_str(self.args);
+        }
+
+        for flag in self.flags {
+            match flag.value {
+                Some(value) => usage.push_str(&format!(" [{} <{value}>]", flag.name)),
+                None => usage.push_str(&format!(" [{}]", flag.name)),
+            }
+        }
+
+        usage
+    }
+}
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "scaffold",
+        args: "<day>",
+        description: "Create the solution module, input and example files for a day.",
+        flags: &[FlagSpec {
+            name: "--download",
+            value: None,
+            description: "Also download the puzzle input and description.",
+        }],
+    },
+    CommandSpec {
+        name: "download",
+        args: "<day>",
+        description: "Download the puzzle input and description for a day via aoc-cli.",
+        flags: &[],
+    },
+    CommandSpec {
+        name: "read",
+        args: "<day>",
+        description: "Print the puzzle description for a day via aoc-cli.",
+        flags: &[],
+    },
+    CommandSpec {
+        name: "solve",
+        args: "<day>",
+        description: "Run the solution for a day against its puzzle input.",
+        flags: &[
+            FlagSpec {
+                name: "--release",
+                value: None,
+                description: "Run an optimized build.",
+            },
+            FlagSpec {
+                name: "--debug",
+                value: None,
+                description: "Run an unoptimized build, overriding `solve.release` in aoc.toml.",
+            },
+            FlagSpec {
+                name: "--time",
+                value: None,
+                description: "Benchmark the solution and print the average execution time.",
+            },
+            FlagSpec {
+                name: "--no-time",
+                value: None,
+                description: "Run the solution once, overriding `solve.time` in aoc.toml.",
+            },
+            FlagSpec {
+                name: "--dhat",
+                value: None,
+                description: "Profile heap allocations with DHAT.",
+            },
+            FlagSpec {
+                name: "--no-dhat",
+                value: None,
+                description:
+                    "Do not profile heap allocations, overriding `solve.dhat` in aoc.toml.",
+            },
+            FlagSpec {
+                name: "--dhat-part",
+                value: Some("part"),
+                description: "Only summarize the heap profile of one part (1 or 2).",
+            },
+            FlagSpec {
+                name: "--submit",
+                value: Some("part"),
+                description: "Submit the answer of the given part (1 or 2) via aoc-cli.",
+            },
+            FlagSpec {
+                name: "--param",
+                value: Some("name=value"),
+                description: "Override a parameter the solution declares. Can be repeated.",
+            },
+            FlagSpec {
+                name: "--ocr",
+                value: None,
+                description:
+                    "Decode answers drawn in block letters, and record and submit the letters.",
+            },
+            FlagSpec {
+                name: "--no-ocr",
+                value: None,
+                description: "Keep drawn answers as they are, overriding `solve.ocr` in aoc.toml.",
+            },
+        ],
+    },
+    CommandSpec {
+        name: "all",
+        args: "",
+        description: "Run the solutions of all scaffolded days.",
+        flags: &[
+            FlagSpec {
+                name: "--release",
+                value: None,
+                description: "Run optimized builds.",
+            },
+            FlagSpec {
+                name: "--time",
+                value: None,
+                description: "Benchmark the solutions and print the total execution time.",
+            },
+        ],
+    },
+    CommandSpec {
+        name: "time",
+        args: "[day]",
+        description: "Benchmark solutions and write the timings to the readme.",
+        flags: &[FlagSpec {
+            name: "--all",
+            value: None,
+            description: "Re-time all days instead of only the ones without benchmarks.",
+        }],
+    },
+    CommandSpec {
+        name: "status",
+        args: "",
+        description: "Print an overview of files, tests, answers, timings and stars per day.",
+        flags: &[FlagSpec {
+            name: "--json",
+            value: None,
+            description: "Print the overview as JSON.",
+        }],
+    },
+    CommandSpec {
+        name: "today",
+        args: "",
+        description: "Scaffold, download and read the current day (requires the `today` feature).",
+        flags: &[],
+    },
+    CommandSpec {
+        name: "completions",
+        args: "<shell>",
+        description: "Print a completion script for bash, zsh or fish.",
+        flags: &[],
+    },
+    CommandSpec {
+        name: "help",
+        args: "[command]",
+        description: "Print this message or the help of the given command.",
+        flags: &[],
+    },
+];
+
+/// Flags that are accepted by every command.
+pub const GLOBAL_FLAGS: &[FlagSpec] = &[
+    FlagSpec {
+        name: "--config",
+        value: Some("path"),
+        description: "Read the project configuration from this file instead of aoc.toml.",
+    },
+    FlagSpec {
+        name: "--year",
+        value: Some("year"),
+        description: "Override the year configured in aoc.toml.",
+    },
+    FlagSpec {
+        name: "--data-dir",
+        value: Some("path"),
+        description: "Override the data directory configured in aoc.toml.",
+    },
+    FlagSpec {
+        name: "--readme",
+        value: Some("path"),
+        description: "Override the readme configured in aoc.toml.",
+    },
+    FlagSpec {
+        name: "--timings-file",
+        value: Some("path"),
+        description: "Override the timings file configured in aoc.toml.",
+    },
+    FlagSpec {
+        name: "--answers-file",
+        value: Some("path"),
+        description: "Override the answers file configured in aoc.toml.",
+    },
+];
+
+/// Looks up the spec of a command by its name.
+pub fn find(name: &str) -> Option<&'static CommandSpec> {
+    COMMANDS.iter().find(|command| command.name == name)
+}
+
+/// Formats the usage line of a command, for use in error messages.
+pub fn usage(name: &str) -> Option<String> {
+    find(name).map(|command| format!("Usage: cargo run -- {}", command.usage()))
+}
+
+pub fn handle(command: Option<String>) {
+    match command {
+        Some(name) => match find(&name) {
+            Some(command) => print_command_help(command),
+            None => {
+                eprintln!("Unknown command: {name}");
+                eprintln!();
+                print_overview();
+                std::process::exit(1);
+            }
+        },
+        None => print_overview(),
+    }
+}
+
+fn print_overview() {
+    println!("{ANSI_BOLD}Usage:{ANSI_RESET} cargo run -- <command> [arguments]");
+    println!();
+    println!("{ANSI_BOLD}Commands:{ANSI_RESET}");
+
+    let width = COMMANDS.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for command in COMMANDS {
+        println!("  {:width$}  {}", command.name, command.description);
+    }
+
+    println!();
+    println!("{ANSI_BOLD}Global flags:{ANSI_RESET}");
+    print_flags(GLOBAL_FLAGS);
+
+    println!();
+    println!("Run `cargo run -- help <command>` for the arguments of a command.");
+}
+
+fn print_command_help(command: &CommandSpec) {
+    println!("{}", command.description);
+    println!();
+    println!(
+        "{ANSI_BOLD}Usage:{ANSI_RESET} cargo run -- {}",
+        command.usage()
+    );
+
+    if command.flags.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{ANSI_BOLD}Flags:{ANSI_RESET}");
+    print_flags(command.flags);
+}
+
+fn print_flags(flags: &[FlagSpec]) {
+    let labels: Vec<String> = flags
+        .iter()
+        .map(|flag| match flag.value {
+            Some(value) => format!("{} <{value}>", flag.name),
+            None => flag.name.to_string(),
+        })
+        .collect();
+    let width = labels.iter().map(String::len).max().unwrap_or(0);
+
+    for (label, flag) in labels.iter().zip(flags) {
+        println!(
+            "  {label:width$}  {ANSI_ITALIC}{}{ANSI_RESET}",
+            flag.description
+        );
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{find, COMMANDS};
+
+    #[test]
+    fn formats_usage_with_flags() {
+        let solve = find("solve").unwrap();
+        assert_eq!(
+            solve.usage(),
+            "solve <day> [--release] [--debug] [--time] [--no-time] [--dhat] [--no-dhat] [--dhat-part <part>] [--submit <part>] [--param <name=value>] [--ocr] [--no-ocr]"
+        );
+    }
+
+    #[test]
+    fn formats_usage_without_arguments() {
+        assert_eq!(find("today").unwrap().usage(), "today");
+    }
+
+    #[test]
+    fn has_unique_command_names() {
+        let mut names: Vec<&str> = COMMANDS.iter().map(|c| c.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), COMMANDS.len());
+    }
+}
@@ -1,6 +1,9 @@
 pub mod all;
+pub mod completions;
 pub mod download;
+pub mod help;
 pub mod read;
 pub mod scaffold;
 pub mod solve;
+pub mod status;
 pub mod time;
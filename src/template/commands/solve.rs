@@ -1,8 +1,15 @@
 use std::process::{Command, Stdio};
 
-use crate::template::Day;
-
-pub fn handle(day: Day, release: bool, time: bool, dhat: bool, submit_part: Option<u8>) {
+use crate::template::runner::RunArguments;
+use crate::template::{dhat_report, Day, Part};
+
+pub fn handle(
+    day: Day,
+    release: bool,
+    dhat: bool,
+    dhat_part: Option<Part>,
+    mut run_args: RunArguments,
+) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -18,13 +25,12 @@ pub fn handle(day: Day, release: bool, time: bool, dhat: bool, submit_part: Opti
 
     cmd_args.push("--".to_string());
 
-    if let Some(submit_part) = submit_part {
-        cmd_args.push("--submit".to_string());
-        cmd_args.push(submit_part.to_string());
-    }
+    // benching would add the allocations of every sample to the heap profile.
+    run_args.time &= !dhat;
+    cmd_args.extend(run_args.to_args());
 
-    if time {
-        cmd_args.push("--time".to_string());
+    if dhat {
+        dhat_report::remove_profiles();
     }
 
     let mut cmd = Command::new("cargo")
@@ -34,5 +40,11 @@ pub fn handle(day: Day, release: bool, time: bool, dhat: bool, submit_part: Opti
         .spawn()
         .unwrap();
 
-    cmd.wait().unwrap();
+    let status = cmd.wait().unwrap();
+
+    if dhat && status.success() {
+        if let Err(e) = dhat_report::print(day, dhat_part) {
+            eprintln!("Failed to read heap profile: {e}");
+        }
+    }
 }
@@ -0,0 +1,384 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use tinyjson::JsonValue;
+
+use crate::template::answers::Answers;
+use crate::template::run_multi::get_path_for_bin;
+use crate::template::timings::Timings;
+use crate::template::{all_days, Config, Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+static STARS_MARKER: &str = "<!--- advent_readme_stars table --->";
+static ACCEPTED_ANSWER_PREFIX: &str = "Your puzzle answer was";
+
+/// Progress of a single part of a day.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PartStatus {
+    /// The test for this part still asserts `None`.
+    pub test_pending: bool,
+    /// The answer recorded by the last `solve` run.
+    pub answer: Option<String>,
+    /// The answer accepted by advent of code, as listed in the puzzle description.
+    pub accepted_answer: Option<String>,
+    /// The latest benchmark timing.
+    pub timing: Option<String>,
+    /// Whether the part earned a star, according to the readme or the puzzle description.
+    pub star: bool,
+}
+
+impl PartStatus {
+    /// Whether the recorded answer matches the accepted one. `None` if either one is unknown.
+    pub fn verified(&self) -> Option<bool> {
+        match (&self.answer, &self.accepted_answer) {
+            (Some(answer), Some(accepted)) => Some(answer == accepted),
+            _ => None,
+        }
+    }
+}
+
+/// Progress of a single day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DayStatus {
+    pub day: Day,
+    pub scaffolded: bool,
+    pub input: bool,
+    pub example: bool,
+    pub puzzle: bool,
+    pub benched: bool,
+    pub parts: [PartStatus; 2],
+}
+
+pub fn handle(json: bool) {
+    let statuses = collect();
+
+    if json {
+        println!("{}", to_json(&statuses).stringify().unwrap());
+    } else {
+        print_table(&statuses);
+    }
+}
+
+/// Gathers the status of every day from the file system.
+pub fn collect() -> Vec<DayStatus> {
+    let config = Config::get();
+    let timings = Timings::read_from_file();
+    let answers = Answers::read_from_file();
+    let readme_stars = fs::read_to_string(&config.readme_path)
+        .map(|readme| parse_readme_stars(&readme))
+        .unwrap_or_default();
+
+    all_days()
+        .map(|day| {
+            let source = fs::read_to_string(get_path_for_bin(day)).ok();
+            let puzzle = read_non_empty(&config.data_folder("puzzles").join(format!("{day}.md")));
+            let accepted = puzzle
+                .as_deref()
+                .map(parse_accepted_answers)
+                .unwrap_or_default();
+            let timing = timings.data.iter().find(|t| t.day == day);
+            let answer = answers.get(day);
+            let stars = readme_stars.get(&day).copied().unwrap_or_default();
+
+            let part = |index: usize| PartStatus {
+                test_pending: source
+                    .as_deref()
+                    .is_some_and(|s| is_test_pending(s, index + 1)),
+                answer: answer.and_then(|a| [&a.part_1, &a.part_2][index].clone()),
+                accepted_answer: accepted.get(index).cloned(),
+                timing: timing.and_then(|t| [&t.part_1, &t.part_2][index].clone()),
+                star: stars[index] || accepted.len() > index,
+            };
+
+            DayStatus {
+                day,
+                scaffolded: source.is_some(),
+                input: read_non_empty(&config.data_folder("inputs").join(format!("{day}.txt")))
+                    .is_some(),
+                example: read_non_empty(&config.data_folder("examples").join(format!("{day}.txt")))
+                    .is_some(),
+                puzzle: puzzle.is_some(),
+                benched: timings.is_day_complete(day),
+                parts: [part(0), part(1)],
+            }
+        })
+        .collect()
+}
+
+fn read_non_empty(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+}
+
+/// Checks whether the test of a part still asserts the scaffolded `None` result.
+fn is_test_pending(source: &str, part: usize) -> bool {
+    let name = match part {
+        1 => "fn test_part_one",
+        _ => "fn test_part_two",
+    };
+
+    source.split(name).nth(1).is_some_and(|rest| {
+        let body = rest.split("fn ").next().unwrap_or(rest);
+        body.contains("assert_eq!(result, None)")
+    })
+}
+
+/// Extracts the answers that were accepted by advent of code from a downloaded puzzle description.
+fn parse_accepted_answers(puzzle: &str) -> Vec<String> {
+    puzzle
+        .match_indices(ACCEPTED_ANSWER_PREFIX)
+        .filter_map(|(idx, _)| {
+            let rest = puzzle[idx + ACCEPTED_ANSWER_PREFIX.len()..].trim_start();
+            let answer = match rest.strip_prefix('`') {
+                Some(quoted) => quoted.split('`').next()?,
+                None => rest.split_whitespace().next()?.trim_end_matches('.'),
+            };
+            Some(answer.to_string())
+        })
+        .collect()
+}
+
+/// Reads the stars of each day from the table maintained by the `readme-stars` workflow.
+fn parse_readme_stars(readme: &str) -> HashMap<Day, [bool; 2]> {
+    let Some(table) = readme.split(STARS_MARKER).nth(1) else {
+        return HashMap::new();
+    };
+
+    table
+        .lines()
+        .filter_map(|line| {
+            let cells: Vec<&str> = line.split('|').map(str::trim).collect();
+            let day = cells
+                .get(1)?
+                .strip_prefix("[Day ")?
+                .split(']')
+                .next()?
+                .parse()
+                .ok()?;
+            Some((
+                day,
+                [cells.get(2)?.contains('⭐'), cells.get(3)?.contains('⭐')],
+            ))
+        })
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn mark(value: bool) -> &'static str {
+    if value {
+        "✔"
+    } else {
+        "✖"
+    }
+}
+
+fn format_answer(part: &PartStatus) -> String {
+    match (&part.answer, part.verified()) {
+        (None, _) => "-".into(),
+        (Some(answer), Some(verified)) => format!("{answer} {}", mark(verified)),
+        (Some(answer), None) => format!("{answer} ?"),
+    }
+}
+
+fn format_timing(part: &PartStatus) -> String {
+    part.timing.clone().unwrap_or_else(|| "-".into())
+}
+
+fn print_table(statuses: &[DayStatus]) {
+    let header = [
+        "Day", "Bin", "Input", "Example", "Puzzle", "Tests", "Part 1", "Part 2", "Time 1",
+        "Time 2", "Stars",
+    ];
+
+    let rows: Vec<Vec<String>> = statuses
+        .iter()
+        .map(|s| {
+            let stars = s.parts.iter().filter(|p| p.star).count();
+            vec![
+                s.day.to_string(),
+                mark(s.scaffolded).into(),
+                mark(s.input).into(),
+                mark(s.example).into(),
+                mark(s.puzzle).into(),
+                if s.scaffolded {
+                    format!(
+                        "{} {}",
+                        mark(!s.parts[0].test_pending),
+                        mark(!s.parts[1].test_pending)
+                    )
+                } else {
+                    "-".into()
+                },
+                format_answer(&s.parts[0]),
+                format_answer(&s.parts[1]),
+                format_timing(&s.parts[0]),
+                format_timing(&s.parts[1]),
+                "⭐".repeat(stars),
+            ]
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|col| {
+            rows.iter()
+                .map(|row| row[col].chars().count())
+                .chain([header[col].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_row = |cells: Vec<String>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| {
+                let padding = width - cell.chars().count();
+                format!("{cell}{}", " ".repeat(padding))
+            })
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    println!(
+        "{ANSI_BOLD}{}{ANSI_RESET}",
+        format_row(header.iter().map(|h| h.to_string()).collect())
+    );
+    for row in rows {
+        println!("{}", format_row(row));
+    }
+
+    let stars: usize = statuses
+        .iter()
+        .map(|s| s.parts.iter().filter(|p| p.star).count())
+        .sum();
+    let scaffolded = statuses.iter().filter(|s| s.scaffolded).count();
+    let benched = statuses.iter().filter(|s| s.benched).count();
+
+    println!();
+    println!(
+        "{ANSI_BOLD}Stars:{ANSI_RESET} {stars}/50  {ANSI_BOLD}Scaffolded:{ANSI_RESET} {scaffolded}/25  {ANSI_BOLD}Benched:{ANSI_RESET} {benched}/25"
+    );
+    println!(
+        "{ANSI_ITALIC}Answers are marked ✔ if they match the accepted answer in the puzzle description, ✖ if they differ and ? if that is unknown.{ANSI_RESET}"
+    );
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn optional_string(value: &Option<String>) -> JsonValue {
+    match value {
+        Some(x) => JsonValue::String(x.clone()),
+        None => JsonValue::Null,
+    }
+}
+
+impl From<&PartStatus> for JsonValue {
+    fn from(value: &PartStatus) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "test_pending".into(),
+            JsonValue::Boolean(value.test_pending),
+        );
+        map.insert("answer".into(), optional_string(&value.answer));
+        map.insert(
+            "accepted_answer".into(),
+            optional_string(&value.accepted_answer),
+        );
+        map.insert(
+            "verified".into(),
+            value.verified().map_or(JsonValue::Null, JsonValue::Boolean),
+        );
+        map.insert("timing".into(), optional_string(&value.timing));
+        map.insert("star".into(), JsonValue::Boolean(value.star));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl From<&DayStatus> for JsonValue {
+    fn from(value: &DayStatus) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("scaffolded".into(), JsonValue::Boolean(value.scaffolded));
+        map.insert("input".into(), JsonValue::Boolean(value.input));
+        map.insert("example".into(), JsonValue::Boolean(value.example));
+        map.insert("puzzle".into(), JsonValue::Boolean(value.puzzle));
+        map.insert("benched".into(), JsonValue::Boolean(value.benched));
+        map.insert("part_1".into(), JsonValue::from(&value.parts[0]));
+        map.insert("part_2".into(), JsonValue::from(&value.parts[1]));
+
+        JsonValue::Object(map)
+    }
+}
+
+fn to_json(statuses: &[DayStatus]) -> JsonValue {
+    let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+    map.insert(
+        "data".into(),
+        JsonValue::Array(statuses.iter().map(JsonValue::from).collect()),
+    );
+
+    JsonValue::Object(map)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{is_test_pending, parse_accepted_answers, parse_readme_stars, PartStatus};
+    use crate::day;
+
+    #[test]
+    fn detects_pending_tests() {
+        let source = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
+        assert!(is_test_pending(source, 1));
+        assert!(is_test_pending(source, 2));
+
+        let solved = source.replacen("assert_eq!(result, None)", "assert_eq!(result, Some(8))", 1);
+        assert!(!is_test_pending(&solved, 1));
+        assert!(is_test_pending(&solved, 2));
+    }
+
+    #[test]
+    fn parses_accepted_answers() {
+        let puzzle = "--- Part One ---\nYour puzzle answer was `55`.\n--- Part Two ---\nYour puzzle answer was 281.\n";
+        assert_eq!(parse_accepted_answers(puzzle), vec!["55", "281"]);
+        assert!(parse_accepted_answers("--- Part One ---").is_empty());
+    }
+
+    #[test]
+    fn parses_readme_stars() {
+        let readme = [
+            "<!--- advent_readme_stars table --->",
+            "| Day | Part 1 | Part 2 |",
+            "| :---: | :---: | :---: |",
+            "| [Day 1](https://adventofcode.com/2023/day/1) | ⭐ | ⭐ |",
+            "| [Day 12](https://adventofcode.com/2023/day/12) | ⭐ |   |",
+            "<!--- advent_readme_stars table --->",
+        ]
+        .join("\n");
+        let stars = parse_readme_stars(&readme);
+        assert_eq!(stars.get(&day!(1)), Some(&[true, true]));
+        assert_eq!(stars.get(&day!(12)), Some(&[true, false]));
+        assert_eq!(stars.get(&day!(2)), None);
+    }
+
+    #[test]
+    fn verifies_answers() {
+        let mut part = PartStatus {
+            answer: Some("55".into()),
+            ..Default::default()
+        };
+        assert_eq!(part.verified(), None);
+        part.accepted_answer = Some("55".into());
+        assert_eq!(part.verified(), Some(true));
+        part.answer = Some("54".into());
+        assert_eq!(part.verified(), Some(false));
+    }
+}
@@ -31,8 +31,8 @@ pub fn handle(day: Option<Day>, recreate_all: bool) {
         Ok(()) => {
             println!("Stored updated benchmarks.");
         }
-        Err(_) => {
-            eprintln!("Failed to store updated benchmarks.");
+        Err(e) => {
+            eprintln!("Failed to store updated benchmarks: {e}");
         }
     }
 }
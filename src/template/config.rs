@@ -0,0 +1,462 @@
+/// Project configuration, read from `aoc.toml` in the repository root.
+///
+/// Values are resolved in this order, later entries winning:
+///  1. built-in defaults.
+///  2. the config file (`aoc.toml`, or the file the `AOC_CONFIG` env var points to).
+///  3. environment variables (`AOC_YEAR`, `AOC_DATA_DIR`, `AOC_README`, `AOC_TIMINGS_FILE`,
+///     `AOC_ANSWERS_FILE`).
+///  4. command-line flags, which `main.rs` mirrors to the env vars above so that child processes pick them up.
+use std::{
+    collections::HashMap,
+    env,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+static DEFAULT_CONFIG_PATH: &str = "aoc.toml";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    /// The year of advent of code that is being solved. Passed to aoc-cli.
+    pub year: Option<u16>,
+    /// Directory holding the `inputs`, `examples` and `puzzles` folders.
+    pub data_dir: PathBuf,
+    /// The readme that benchmarks are written to.
+    pub readme_path: PathBuf,
+    /// The file aoc-cli reads the session cookie from. aoc-cli's default is used if not set.
+    pub session_file: Option<PathBuf>,
+    /// The file the answers of the last runs are recorded to. Use [`Config::answers_file`],
+    /// which falls back to `answers.json` in the data directory if not set.
+    pub answers_file: Option<PathBuf>,
+    pub benchmark: BenchmarkConfig,
+    pub solve: SolveDefaults,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchmarkConfig {
+    /// The file timings are stored in. Use [`Config::timings_file`], which falls back to
+    /// `timings.json` in the data directory if not set.
+    pub timings_file: Option<PathBuf>,
+    /// The lower bound of iterations when benching a solution.
+    pub min_samples: u128,
+    /// The upper bound of iterations when benching a solution.
+    pub max_samples: u128,
+    /// The approximate time a solution is benched for, in milliseconds.
+    pub target_millis: u64,
+}
+
+/// Flags that `cargo solve` uses when they are not passed on the command-line.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SolveDefaults {
+    pub release: bool,
+    pub time: bool,
+    pub dhat: bool,
+    pub ocr: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            year: None,
+            data_dir: PathBuf::from("data"),
+            readme_path: PathBuf::from("README.md"),
+            session_file: None,
+            answers_file: None,
+            benchmark: BenchmarkConfig::default(),
+            solve: SolveDefaults::default(),
+        }
+    }
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            timings_file: None,
+            min_samples: 10,
+            max_samples: 10000,
+            target_millis: 1000,
+        }
+    }
+}
+
+impl Config {
+    /// Returns the configuration of the current process. The config file is read on first access.
+    pub fn get() -> &'static Config {
+        static CONFIG: OnceLock<Config> = OnceLock::new();
+
+        CONFIG.get_or_init(|| match Config::load() {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Failed to read config: {e}");
+                std::process::exit(1);
+            }
+        })
+    }
+
+    /// Reads the config file and applies environment overrides.
+    pub fn load() -> Result<Config, Error> {
+        let path = env::var("AOC_CONFIG").unwrap_or_else(|_| DEFAULT_CONFIG_PATH.into());
+
+        let mut config = match fs::read_to_string(&path) {
+            Ok(s) => Config::from_toml(&s)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Config::default(),
+            Err(e) => return Err(Error::IO(e)),
+        };
+
+        config.apply_overrides(|name| env::var(name).ok())?;
+        Ok(config)
+    }
+
+    /// Applies the overrides of the `AOC_*` env vars, as looked up by `var`.
+    fn apply_overrides(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<(), Error> {
+        if let Some(year) = var("AOC_YEAR") {
+            self.year = Some(
+                year.parse()
+                    .map_err(|_| Error::Parser(format!("invalid AOC_YEAR `{year}`.")))?,
+            );
+        }
+        if let Some(path) = var("AOC_DATA_DIR") {
+            self.data_dir = expand_home(&path);
+        }
+        if let Some(path) = var("AOC_README") {
+            self.readme_path = expand_home(&path);
+        }
+        if let Some(path) = var("AOC_TIMINGS_FILE") {
+            self.benchmark.timings_file = Some(expand_home(&path));
+        }
+        if let Some(path) = var("AOC_ANSWERS_FILE") {
+            self.answers_file = Some(expand_home(&path));
+        }
+        Ok(())
+    }
+
+    /// Parses a config from the contents of a config file. Missing keys fall back to their defaults.
+    pub fn from_toml(s: &str) -> Result<Config, Error> {
+        let table = parse_toml(s)?;
+        let mut config = Config::default();
+
+        for ((section, key), value) in &table {
+            let name = match section.as_str() {
+                "" => key.clone(),
+                section => format!("{section}.{key}"),
+            };
+
+            match name.as_str() {
+                "year" => config.year = Some(value.integer(&name)?),
+                "data_dir" => config.data_dir = value.path(&name)?,
+                "readme" => config.readme_path = value.path(&name)?,
+                "session_file" => config.session_file = Some(value.path(&name)?),
+                "answers_file" => config.answers_file = Some(value.path(&name)?),
+                "benchmark.timings_file" => {
+                    config.benchmark.timings_file = Some(value.path(&name)?);
+                }
+                "benchmark.min_samples" => config.benchmark.min_samples = value.integer(&name)?,
+                "benchmark.max_samples" => config.benchmark.max_samples = value.integer(&name)?,
+                "benchmark.target_millis" => {
+                    config.benchmark.target_millis = value.integer(&name)?;
+                }
+                "solve.release" => config.solve.release = value.boolean(&name)?,
+                "solve.time" => config.solve.time = value.boolean(&name)?,
+                "solve.dhat" => config.solve.dhat = value.boolean(&name)?,
+                "solve.ocr" => config.solve.ocr = value.boolean(&name)?,
+                _ => return Err(Error::Parser(format!("unknown key `{name}`."))),
+            }
+        }
+
+        if config.benchmark.min_samples > config.benchmark.max_samples {
+            return Err(Error::Parser(
+                "`benchmark.min_samples` must not exceed `benchmark.max_samples`.".into(),
+            ));
+        }
+
+        Ok(config)
+    }
+
+    /// Path of the folder for a kind of data file, e.g. `data/inputs`.
+    pub fn data_folder(&self, folder: &str) -> PathBuf {
+        self.data_dir.join(folder)
+    }
+
+    /// The file timings are stored in, `timings.json` in the data directory by default.
+    pub fn timings_file(&self) -> PathBuf {
+        self.benchmark
+            .timings_file
+            .clone()
+            .unwrap_or_else(|| self.data_dir.join("timings.json"))
+    }
+
+    /// The file answers are recorded to, `answers.json` in the data directory by default.
+    pub fn answers_file(&self) -> PathBuf {
+        self.answers_file
+            .clone()
+            .unwrap_or_else(|| self.data_dir.join("answers.json"))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug)]
+pub enum Error {
+    Parser(String),
+    IO(io::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parser(message) => write!(f, "{message}"),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/* -------------------------------------------------------------------------- */
+
+/// A value of the supported TOML subset.
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+}
+
+impl Value {
+    fn integer<T: TryFrom<i64>>(&self, name: &str) -> Result<T, Error> {
+        match self {
+            Value::Integer(x) => {
+                T::try_from(*x).map_err(|_| Error::Parser(format!("`{name}` is out of range.")))
+            }
+            _ => Err(Error::Parser(format!(
+                "expected `{name}` to be an integer."
+            ))),
+        }
+    }
+
+    fn boolean(&self, name: &str) -> Result<bool, Error> {
+        match self {
+            Value::Boolean(x) => Ok(*x),
+            _ => Err(Error::Parser(format!("expected `{name}` to be a boolean."))),
+        }
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf, Error> {
+        match self {
+            Value::String(s) => Ok(expand_home(s)),
+            _ => Err(Error::Parser(format!("expected `{name}` to be a string."))),
+        }
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Parses the small subset of TOML the config needs: `[section]` headers and `key = value` pairs,
+/// where values are basic strings, integers or booleans. Comments start with `#`.
+fn parse_toml(s: &str) -> Result<HashMap<(String, String), Value>, Error> {
+    let mut table = HashMap::new();
+    let mut section = String::new();
+
+    for (idx, raw_line) in s.lines().enumerate() {
+        let line_number = idx + 1;
+        let err = |message: &str| Error::Parser(format!("line {line_number}: {message}"));
+        let line = strip_comment(raw_line).trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            section = header
+                .strip_suffix(']')
+                .ok_or_else(|| err("expected `]` to close section header."))?
+                .trim()
+                .to_string();
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| err("expected `key = value`."))?;
+        let key = key.trim();
+        let value = value.trim();
+
+        if key.is_empty() {
+            return Err(err("expected a key before `=`."));
+        }
+
+        let value = if let Some(s) = value.strip_prefix('"') {
+            Value::String(
+                s.strip_suffix('"')
+                    .ok_or_else(|| err("unterminated string."))?
+                    .replace("\\\\", "\\")
+                    .replace("\\\"", "\""),
+            )
+        } else if value == "true" || value == "false" {
+            Value::Boolean(value == "true")
+        } else {
+            Value::Integer(
+                value
+                    .replace('_', "")
+                    .parse()
+                    .map_err(|_| err(&format!("unsupported value `{value}`.")))?,
+            )
+        };
+
+        let previous = table.insert((section.clone(), key.to_string()), value);
+        if previous.is_some() {
+            return Err(err(&format!("duplicate key `{key}`.")));
+        }
+    }
+
+    Ok(table)
+}
+
+/// Removes a trailing comment, ignoring `#` characters inside of strings.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (idx, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..idx],
+            _ => {}
+        }
+    }
+
+    line
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Config;
+    use std::{collections::HashMap, path::PathBuf};
+
+    #[test]
+    fn uses_defaults_for_empty_file() {
+        let config = Config::from_toml("").unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn parses_all_keys() {
+        let config = Config::from_toml(
+            r#"
+            # advent of code settings
+            year = 2022
+            data_dir = "puzzles" # trailing comment
+            readme = "docs/#README.md"
+            session_file = "/tmp/session"
+            answers_file = "answers.json"
+
+            [benchmark]
+            timings_file = "puzzles/timings.json"
+            min_samples = 5
+            max_samples = 1_000
+            target_millis = 200
+
+            [solve]
+            release = true
+            time = true
+            dhat = false
+            ocr = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.year, Some(2022));
+        assert_eq!(config.data_dir, PathBuf::from("puzzles"));
+        assert_eq!(
+            config.data_folder("inputs"),
+            PathBuf::from("puzzles/inputs")
+        );
+        assert_eq!(config.readme_path, PathBuf::from("docs/#README.md"));
+        assert_eq!(config.session_file, Some(PathBuf::from("/tmp/session")));
+        assert_eq!(config.answers_file(), PathBuf::from("answers.json"));
+        assert_eq!(config.timings_file(), PathBuf::from("puzzles/timings.json"));
+        assert_eq!(config.benchmark.min_samples, 5);
+        assert_eq!(config.benchmark.max_samples, 1000);
+        assert_eq!(config.benchmark.target_millis, 200);
+        assert!(config.solve.release);
+        assert!(config.solve.time);
+        assert!(!config.solve.dhat);
+        assert!(config.solve.ocr);
+    }
+
+    #[test]
+    fn derives_files_from_data_dir() {
+        let config = Config::from_toml("data_dir = \"puzzles\"").unwrap();
+        assert_eq!(config.timings_file(), PathBuf::from("puzzles/timings.json"));
+        assert_eq!(config.answers_file(), PathBuf::from("puzzles/answers.json"));
+        assert_eq!(
+            Config::default().timings_file(),
+            PathBuf::from("data/timings.json")
+        );
+    }
+
+    #[test]
+    fn applies_overrides() {
+        let mut config = Config::from_toml("year = 2022\ndata_dir = \"puzzles\"").unwrap();
+        let vars = HashMap::from([
+            ("AOC_YEAR", "2015"),
+            ("AOC_DATA_DIR", "other"),
+            ("AOC_README", "docs/README.md"),
+        ]);
+        config
+            .apply_overrides(|name| vars.get(name).map(|value| value.to_string()))
+            .unwrap();
+
+        assert_eq!(config.year, Some(2015));
+        assert_eq!(config.data_folder("inputs"), PathBuf::from("other/inputs"));
+        assert_eq!(config.readme_path, PathBuf::from("docs/README.md"));
+        assert_eq!(config.timings_file(), PathBuf::from("other/timings.json"));
+        assert_eq!(config.answers_file(), PathBuf::from("other/answers.json"));
+
+        config
+            .apply_overrides(|name| (name == "AOC_TIMINGS_FILE").then(|| "t.json".to_string()))
+            .unwrap();
+        assert_eq!(config.timings_file(), PathBuf::from("t.json"));
+        assert!(config
+            .apply_overrides(|name| (name == "AOC_YEAR").then(|| "soon".to_string()))
+            .is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_unknown_keys() {
+        Config::from_toml("[solve]\nsubmit = 1").unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_mistyped_values() {
+        Config::from_toml("year = \"2023\"").unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_duplicate_keys() {
+        Config::from_toml("year = 2022\nyear = 2023").unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_inverted_sample_bounds() {
+        Config::from_toml("[benchmark]\nmin_samples = 10\nmax_samples = 5").unwrap();
+    }
+}
@@ -0,0 +1,432 @@
+/// Module that summarizes the heap profiles written by `cargo solve --dhat`.
+/// The profile format is described in `dhat/dh_main.c` in Valgrind's source code.
+use std::{collections::HashMap, fmt::Display, fs, io, str::FromStr};
+
+use tinyjson::JsonValue;
+
+use crate::template::run_multi::get_path_for_bin;
+use crate::template::{Day, Part, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+static TOP_SITES: usize = 10;
+
+#[derive(Debug)]
+pub enum Error {
+    Parser(String),
+    IO(io::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parser(message) => write!(f, "{message}"),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+/// Allocation statistics of a single program point, i.e. a unique backtrace.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProgramPoint {
+    pub total_bytes: u64,
+    pub total_blocks: u64,
+    pub gmax_bytes: u64,
+    pub gmax_blocks: u64,
+    pub end_bytes: u64,
+    pub end_blocks: u64,
+    /// Frames from the allocation itself outwards.
+    pub frames: Vec<Frame>,
+}
+
+/// A single frame of a backtrace, e.g. `0x10: _05::part_one (src/bin/05.rs:12:5)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub function: String,
+    pub file: String,
+    pub line: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profile {
+    pub program_points: Vec<ProgramPoint>,
+}
+
+/// Allocations aggregated by their source location.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AllocationSite {
+    pub function: String,
+    pub file: String,
+    pub line: usize,
+    pub total_bytes: u64,
+    pub total_blocks: u64,
+    pub gmax_bytes: u64,
+}
+
+impl Profile {
+    fn sum(&self, f: impl Fn(&ProgramPoint) -> u64) -> u64 {
+        self.program_points.iter().map(f).sum()
+    }
+
+    /// Groups allocations by the innermost frame in the solution's source file,
+    /// falling back to the innermost frame outside of the standard library and the allocator.
+    pub fn sites(&self, day: Day) -> Vec<AllocationSite> {
+        let bin_path = get_path_for_bin(day);
+        let bin_path = bin_path.trim_start_matches("./");
+
+        let mut sites: HashMap<(String, usize), AllocationSite> = HashMap::new();
+
+        for pp in &self.program_points {
+            let Some(frame) = pp
+                .frames
+                .iter()
+                .find(|f| f.file.ends_with(bin_path))
+                .or_else(|| {
+                    pp.frames
+                        .iter()
+                        .find(|f| !f.is_runtime() && f.file != "???")
+                })
+                .or_else(|| pp.frames.first())
+            else {
+                continue;
+            };
+
+            let site = sites
+                .entry((frame.file.clone(), frame.line))
+                .or_insert_with(|| AllocationSite {
+                    function: frame.function.clone(),
+                    file: frame.file.clone(),
+                    line: frame.line,
+                    total_bytes: 0,
+                    total_blocks: 0,
+                    gmax_bytes: 0,
+                });
+
+            site.total_bytes += pp.total_bytes;
+            site.total_blocks += pp.total_blocks;
+            site.gmax_bytes += pp.gmax_bytes;
+        }
+
+        let mut sites: Vec<AllocationSite> = sites.into_values().collect();
+        sites.sort_unstable_by(|a, b| {
+            b.total_bytes
+                .cmp(&a.total_bytes)
+                .then_with(|| a.file.cmp(&b.file))
+                .then_with(|| a.line.cmp(&b.line))
+        });
+        sites
+    }
+}
+
+impl Frame {
+    /// Whether the frame belongs to the allocator, the standard library or the profiler.
+    fn is_runtime(&self) -> bool {
+        // trait impls are formatted as `<T as alloc::vec::SpecFromElem>::from_elem`.
+        let function = self.function.trim_start_matches('<');
+        let (ty, function) = function.split_once(" as ").unwrap_or((function, function));
+        ["alloc::", "core::", "std::", "dhat::", "__rust"]
+            .iter()
+            .any(|prefix| ty.starts_with(prefix) || function.starts_with(prefix))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl FromStr for Frame {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || format!("unexpected frame format: `{s}`.");
+
+        // strip the instruction pointer, if present.
+        let s = match s.split_once(": ") {
+            Some((ip, rest)) if ip.starts_with("0x") => rest,
+            _ => s,
+        };
+
+        let Some((function, location)) = s.rsplit_once(" (") else {
+            return Ok(Frame {
+                function: s.to_string(),
+                file: "???".into(),
+                line: 0,
+            });
+        };
+
+        let mut parts = location.trim_end_matches(')').rsplitn(3, ':');
+        let _column = parts.next().ok_or_else(err)?;
+        let line = parts.next().ok_or_else(err)?.parse().map_err(|_| err())?;
+        let file = parts.next().ok_or_else(err)?;
+
+        Ok(Frame {
+            function: function.to_string(),
+            file: file.to_string(),
+            line,
+        })
+    }
+}
+
+impl TryFrom<String> for Profile {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+        let json = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?;
+
+        let frames: Vec<Option<Frame>> = json
+            .get("ftbl")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .ok_or("expected `json.ftbl` to be an array.")?
+            .iter()
+            .map(|v| {
+                let s = v.get::<String>().ok_or("expected frames to be strings.")?;
+                // the first entry is a synthetic `[root]` frame.
+                if s == "[root]" {
+                    Ok(None)
+                } else {
+                    Frame::from_str(s).map(Some)
+                }
+            })
+            .collect::<Result<_, String>>()?;
+
+        let program_points = json
+            .get("pps")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .ok_or("expected `json.pps` to be an array.")?
+            .iter()
+            .map(|v| {
+                let pp = v
+                    .get::<HashMap<String, JsonValue>>()
+                    .ok_or("expected program point to be an object.")?;
+                let number = |key: &str| {
+                    pp.get(key)
+                        .and_then(|v| v.get::<f64>())
+                        .map_or(0, |x| *x as u64)
+                };
+
+                let frames = pp
+                    .get("fs")
+                    .and_then(|v| v.get::<Vec<JsonValue>>())
+                    .ok_or("expected `pp.fs` to be an array.")?
+                    .iter()
+                    .map(|idx| {
+                        let idx = *idx.get::<f64>().ok_or("expected frame index.")? as usize;
+                        frames
+                            .get(idx)
+                            .cloned()
+                            .ok_or_else(|| format!("frame index {idx} out of bounds."))
+                    })
+                    .collect::<Result<Vec<Option<Frame>>, String>>()?;
+
+                Ok(ProgramPoint {
+                    total_bytes: number("tb"),
+                    total_blocks: number("tbk"),
+                    gmax_bytes: number("gb"),
+                    gmax_blocks: number("gbk"),
+                    end_bytes: number("eb"),
+                    end_blocks: number("ebk"),
+                    frames: frames.into_iter().flatten().collect(),
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Profile { program_points })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn separate_thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut s = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            s.push(',');
+        }
+        s.push(c);
+    }
+    s
+}
+
+/// The file that the heap profile of `part` is written to, relative to the repo root.
+pub fn profile_path(part: Part) -> String {
+    format!("dhat-heap-{part}.json")
+}
+
+/// Removes the profiles of an earlier run, so that a part that does not run anymore is not
+/// summarized again.
+pub fn remove_profiles() {
+    for part in [Part::One, Part::Two] {
+        let _ = fs::remove_file(profile_path(part));
+    }
+}
+
+/// Reads the profiles written by the last `--dhat` run and prints a summary of each part, or of
+/// `part` only, to the terminal.
+pub fn print(day: Day, part: Option<Part>) -> Result<(), Error> {
+    let parts = match part {
+        Some(part) => vec![part],
+        // a day may only implement part one.
+        None => [Part::One, Part::Two]
+            .into_iter()
+            .filter(|part| fs::metadata(profile_path(*part)).is_ok())
+            .collect(),
+    };
+    if parts.is_empty() {
+        return Err(Error::Parser("no heap profile was written.".into()));
+    }
+
+    for part in parts {
+        let profile =
+            Profile::try_from(fs::read_to_string(profile_path(part))?).map_err(Error::Parser)?;
+        print_profile(&profile, day, part);
+    }
+    Ok(())
+}
+
+fn print_profile(profile: &Profile, day: Day, part: Part) {
+    let source = fs::read_to_string(get_path_for_bin(day)).unwrap_or_default();
+    let source_lines: Vec<&str> = source.lines().collect();
+
+    println!();
+    println!("{ANSI_BOLD}Heap profile (part {part}){ANSI_RESET}");
+    println!(
+        "Total:     {} bytes in {} blocks",
+        separate_thousands(profile.sum(|pp| pp.total_bytes)),
+        separate_thousands(profile.sum(|pp| pp.total_blocks))
+    );
+    println!(
+        "At t-gmax: {} bytes in {} blocks",
+        separate_thousands(profile.sum(|pp| pp.gmax_bytes)),
+        separate_thousands(profile.sum(|pp| pp.gmax_blocks))
+    );
+    println!(
+        "At t-end:  {} bytes in {} blocks",
+        separate_thousands(profile.sum(|pp| pp.end_bytes)),
+        separate_thousands(profile.sum(|pp| pp.end_blocks))
+    );
+
+    let sites = profile.sites(day);
+    if sites.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{ANSI_BOLD}Top allocation sites:{ANSI_RESET}");
+
+    for (idx, site) in sites.iter().take(TOP_SITES).enumerate() {
+        println!(
+            "{:>2}. {} bytes in {} blocks (at t-gmax: {} bytes) {ANSI_ITALIC}{}:{}{ANSI_RESET}",
+            idx + 1,
+            separate_thousands(site.total_bytes),
+            separate_thousands(site.total_blocks),
+            separate_thousands(site.gmax_bytes),
+            site.file,
+            site.line
+        );
+
+        let line = site
+            .line
+            .checked_sub(1)
+            .and_then(|idx| source_lines.get(idx))
+            .filter(|_| {
+                site.file
+                    .ends_with(get_path_for_bin(day).trim_start_matches("./"))
+            });
+
+        match line {
+            Some(line) => println!("    | {}", line.trim()),
+            None => println!("    | {}", site.function),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{profile_path, separate_thousands, Frame, Profile};
+    use crate::day;
+    use crate::template::Part;
+    use std::str::FromStr;
+
+    fn get_mock_profile() -> Profile {
+        let json = r#"{
+            "dhatFileVersion": 2,
+            "mode": "rust-heap",
+            "pps": [
+                { "tb": 1000, "tbk": 2, "gb": 500, "gbk": 1, "eb": 0, "ebk": 0, "fs": [1, 2, 4] },
+                { "tb": 24, "tbk": 1, "gb": 24, "gbk": 1, "eb": 24, "ebk": 1, "fs": [1, 3, 5] },
+                { "tb": 8, "tbk": 1, "gb": 0, "gbk": 0, "eb": 0, "ebk": 0, "fs": [1, 2, 4] }
+            ],
+            "ftbl": [
+                "[root]",
+                "0x1: alloc::alloc::alloc (/rustc/abc/library/alloc/src/alloc.rs:98:9)",
+                "0x2: _05::parse (src/bin/05.rs:12:18)",
+                "0x3: _05::part_two (src/bin/05.rs:40:5)",
+                "0x4: _05::part_one (src/bin/05.rs:30:5)",
+                "0x5: _05::main (src/bin/05.rs:1:1)"
+            ]
+        }"#;
+        Profile::try_from(json.to_string()).unwrap()
+    }
+
+    #[test]
+    fn parses_frames() {
+        let frame = Frame::from_str("0x10: _05::part_one (src/bin/05.rs:30:5)").unwrap();
+        assert_eq!(frame.function, "_05::part_one");
+        assert_eq!(frame.file, "src/bin/05.rs");
+        assert_eq!(frame.line, 30);
+
+        let frame = Frame::from_str("0x10: ???").unwrap();
+        assert_eq!(frame.function, "???");
+        assert_eq!(frame.line, 0);
+    }
+
+    #[test]
+    fn detects_runtime_frames() {
+        let frame = |s: &str| Frame::from_str(s).unwrap();
+        assert!(frame("0x1: alloc::alloc::alloc (alloc/src/alloc.rs:98:9)").is_runtime());
+        assert!(frame("0x1: <T as alloc::vec::spec_from_elem::SpecFromElem>::from_elem (src/vec/spec_from_elem.rs:14:5)").is_runtime());
+        assert!(!frame("0x1: regex::Regex::new (src/regex.rs:1:1)").is_runtime());
+        assert!(!frame("0x1: _01::part_one (src/bin/01.rs:1:1)").is_runtime());
+    }
+
+    #[test]
+    fn parses_profiles() {
+        let profile = get_mock_profile();
+        assert_eq!(profile.program_points.len(), 3);
+        assert_eq!(profile.program_points[0].total_bytes, 1000);
+        assert_eq!(profile.program_points[1].end_blocks, 1);
+        assert_eq!(profile.program_points[0].frames.len(), 3);
+    }
+
+    #[test]
+    fn groups_sites_by_solution_line() {
+        let sites = get_mock_profile().sites(day!(5));
+        assert_eq!(sites.len(), 2);
+        assert_eq!(sites[0].line, 12);
+        assert_eq!(sites[0].total_bytes, 1008);
+        assert_eq!(sites[0].total_blocks, 3);
+        assert_eq!(sites[1].line, 40);
+    }
+
+    #[test]
+    fn names_profiles_by_part() {
+        assert_eq!(profile_path(Part::One), "dhat-heap-1.json");
+        assert_eq!(profile_path(Part::Two), "dhat-heap-2.json");
+    }
+
+    #[test]
+    fn formats_numbers() {
+        assert_eq!(separate_thousands(0), "0");
+        assert_eq!(separate_thousands(999), "999");
+        assert_eq!(separate_thousands(1000), "1,000");
+        assert_eq!(separate_thousands(1234567), "1,234,567");
+    }
+}
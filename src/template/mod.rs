@@ -2,11 +2,20 @@ use std::{env, fs};
 
 pub mod aoc_cli;
 pub mod commands;
+pub mod config;
+pub mod ocr;
 pub mod runner;
 
+pub use config::Config;
 pub use day::*;
+pub use params::*;
+pub use part::*;
 
+mod answers;
 mod day;
+mod dhat_report;
+mod params;
+mod part;
 mod readme_benchmarks;
 mod run_multi;
 mod timings;
@@ -19,18 +28,19 @@ pub const ANSI_RESET: &str = "\x1b[0m";
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
+    let filepath = cwd
+        .join(Config::get().data_folder(folder))
+        .join(format!("{day}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
-pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
+pub fn read_file_part(folder: &str, day: Day, part: Part) -> String {
     let cwd = env::current_dir().unwrap();
     let filepath = cwd
-        .join("data")
-        .join(folder)
+        .join(Config::get().data_folder(folder))
         .join(format!("{day}-{part}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
@@ -39,19 +49,67 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// Solutions can declare parameters with defaults, e.g. `solution!(11, params { factor: u64 = 1_000_000 })`.
+/// This generates a `Params` struct that parts receive as a second argument, `fn part_two(input: &str, params: &Params)`.
+/// Tests construct `Params` directly, `cargo solve 11 --param factor=10` overrides the defaults.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
-        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
+        $crate::solution!(@impl $day, (), [part_one, One] [part_two, Two]);
     };
     ($day:expr, 1) => {
-        $crate::solution!(@impl $day, [part_one, 1]);
+        $crate::solution!(@impl $day, (), [part_one, One]);
     };
     ($day:expr, 2) => {
-        $crate::solution!(@impl $day, [part_two, 2]);
+        $crate::solution!(@impl $day, (), [part_two, Two]);
+    };
+    ($day:expr, params { $($params:tt)* }) => {
+        $crate::solution!(@params $($params)*);
+        $crate::solution!(@impl $day, Params, [part_one, One] [part_two, Two]);
+    };
+    ($day:expr, 1, params { $($params:tt)* }) => {
+        $crate::solution!(@params $($params)*);
+        $crate::solution!(@impl $day, Params, [part_one, One]);
     };
+    ($day:expr, 2, params { $($params:tt)* }) => {
+        $crate::solution!(@params $($params)*);
+        $crate::solution!(@impl $day, Params, [part_two, Two]);
+    };
+
+    (@params $( $name:ident : $type:ty = $default:expr ),* $(,)?) => {
+        /// Parameters of the solution. The defaults apply to the real input.
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct Params {
+            $( pub $name: $type, )*
+        }
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+        impl Default for Params {
+            fn default() -> Self {
+                Self {
+                    $( $name: $default, )*
+                }
+            }
+        }
+
+        impl $crate::template::SolutionParams for Params {
+            fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+                match name {
+                    $(
+                        stringify!($name) => {
+                            self.$name = value.parse().map_err(|_| {
+                                format!("invalid value `{value}` for parameter `{name}`.")
+                            })?;
+                        }
+                    )*
+                    _ => return Err(format!("unknown parameter `{name}`.")),
+                }
+                Ok(())
+            }
+        }
+    };
+
+    (@impl $day:expr, $params:ty, $( [$func:expr, $part:ident] )*) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -61,8 +119,11 @@ macro_rules! solution {
 
         fn main() {
             use $crate::template::runner::*;
+
+            let args = RunArguments::from_env();
+            let params: $params = args.solution_params();
             let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            $( run_part($func, input.as_str(), DAY, $crate::template::Part::$part, &args, &params); )*
         }
     };
 }
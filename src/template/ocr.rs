@@ -0,0 +1,263 @@
+/// Module that recognizes answers drawn in the block-letter fonts used by advent of code.
+/// Two fonts are known: a small one that is 6 pixels high and a large one that is 10 pixels high.
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The drawing contains no lit pixels.
+    Empty,
+    /// No font of this height is known.
+    UnsupportedHeight(usize),
+    /// The letter at this index (starting at 0) is not part of the font.
+    UnknownGlyph { index: usize, glyph: String },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Empty => write!(f, "drawing is empty."),
+            Error::UnsupportedHeight(height) => {
+                write!(f, "no font is {height} pixels high, expected 6 or 10.")
+            }
+            Error::UnknownGlyph { index, glyph } => {
+                write!(f, "letter {} is not recognized:\n{glyph}", index + 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+static FONT_6: &[(char, &str)] = &[
+    ('A', ".##.|#..#|#..#|####|#..#|#..#"),
+    ('B', "###.|#..#|###.|#..#|#..#|###."),
+    ('C', ".##.|#..#|#...|#...|#..#|.##."),
+    ('E', "####|#...|###.|#...|#...|####"),
+    ('F', "####|#...|###.|#...|#...|#..."),
+    ('G', ".##.|#..#|#...|#.##|#..#|.###"),
+    ('H', "#..#|#..#|####|#..#|#..#|#..#"),
+    ('I', "###|.#.|.#.|.#.|.#.|###"),
+    ('J', "..##|...#|...#|...#|#..#|.##."),
+    ('K', "#..#|#.#.|##..|#.#.|#.#.|#..#"),
+    ('L', "#...|#...|#...|#...|#...|####"),
+    ('O', ".##.|#..#|#..#|#..#|#..#|.##."),
+    ('P', "###.|#..#|#..#|###.|#...|#..."),
+    ('R', "###.|#..#|#..#|###.|#.#.|#..#"),
+    ('S', ".###|#...|#...|.##.|...#|###."),
+    ('U', "#..#|#..#|#..#|#..#|#..#|.##."),
+    ('Y', "#...#|#...#|.#.#.|..#..|..#..|..#.."),
+    ('Z', "####|...#|..#.|.#..|#...|####"),
+];
+
+static FONT_10: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..|.#..#.|#....#|#....#|#....#|######|#....#|#....#|#....#|#....#",
+    ),
+    (
+        'B',
+        "#####.|#....#|#....#|#....#|#####.|#....#|#....#|#....#|#....#|#####.",
+    ),
+    (
+        'C',
+        ".####.|#....#|#.....|#.....|#.....|#.....|#.....|#.....|#....#|.####.",
+    ),
+    (
+        'E',
+        "######|#.....|#.....|#.....|#####.|#.....|#.....|#.....|#.....|######",
+    ),
+    (
+        'F',
+        "######|#.....|#.....|#.....|#####.|#.....|#.....|#.....|#.....|#.....",
+    ),
+    (
+        'G',
+        ".####.|#....#|#.....|#.....|#.....|#..###|#....#|#....#|#...##|.###.#",
+    ),
+    (
+        'H',
+        "#....#|#....#|#....#|#....#|######|#....#|#....#|#....#|#....#|#....#",
+    ),
+    (
+        'J',
+        "...###|....#.|....#.|....#.|....#.|....#.|....#.|#...#.|#...#.|.###..",
+    ),
+    (
+        'K',
+        "#....#|#...#.|#..#..|#.#...|##....|##....|#.#...|#..#..|#...#.|#....#",
+    ),
+    (
+        'L',
+        "#.....|#.....|#.....|#.....|#.....|#.....|#.....|#.....|#.....|######",
+    ),
+    (
+        'N',
+        "#....#|##...#|##...#|#.#..#|#.#..#|#..#.#|#..#.#|#...##|#...##|#....#",
+    ),
+    (
+        'P',
+        "#####.|#....#|#....#|#....#|#####.|#.....|#.....|#.....|#.....|#.....",
+    ),
+    (
+        'R',
+        "#####.|#....#|#....#|#....#|#####.|#..#..|#...#.|#...#.|#....#|#....#",
+    ),
+    (
+        'X',
+        "#....#|#....#|.#..#.|.#..#.|..##..|..##..|.#..#.|.#..#.|#....#|#....#",
+    ),
+    (
+        'Z',
+        "######|.....#|.....#|....#.|...#..|..#...|.#....|#.....|#.....|######",
+    ),
+];
+
+/// Recognizes the letters of a drawing, where `#` and `█` are lit pixels and all other characters are dark.
+///
+/// ```
+/// # use advent_of_code::template::ocr;
+/// let art = "#..#.###.\n#..#..#..\n####..#..\n#..#..#..\n#..#..#..\n#..#.###.";
+/// assert_eq!(ocr::recognize(art).unwrap(), "HI");
+/// ```
+pub fn recognize(s: &str) -> Result<String, Error> {
+    let grid: Vec<Vec<bool>> = s
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#' || c == '█').collect())
+        .collect();
+
+    recognize_grid(&grid)
+}
+
+/// Recognizes the letters of a drawing given as rows of pixels, where `true` is a lit pixel.
+pub fn recognize_grid(grid: &[Vec<bool>]) -> Result<String, Error> {
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |y: usize, x: usize| grid[y].get(x).copied().unwrap_or(false);
+
+    // ignore blank rows above and below the letters.
+    let rows: Vec<usize> = (0..grid.len())
+        .filter(|&y| (0..width).any(|x| lit(y, x)))
+        .collect();
+    let (Some(&top), Some(&bottom)) = (rows.first(), rows.last()) else {
+        return Err(Error::Empty);
+    };
+
+    let height = bottom - top + 1;
+    let font = match height {
+        6 => FONT_6,
+        10 => FONT_10,
+        _ => return Err(Error::UnsupportedHeight(height)),
+    };
+
+    let column_is_lit = |x: usize| (top..=bottom).any(|y| lit(y, x));
+
+    // letters are separated by at least one blank column.
+    let mut letters: Vec<(usize, usize)> = vec![];
+    let mut start = None;
+    for x in 0..=width {
+        match (start, x < width && column_is_lit(x)) {
+            (None, true) => start = Some(x),
+            (Some(from), false) => {
+                letters.push((from, x));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    letters
+        .iter()
+        .enumerate()
+        .map(|(index, &(from, to))| {
+            let glyph = (top..=bottom)
+                .map(|y| {
+                    (from..to)
+                        .map(|x| if lit(y, x) { '#' } else { '.' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<String>>()
+                .join("|");
+
+            font.iter()
+                .find(|(_, pattern)| *pattern == glyph)
+                .map(|(c, _)| *c)
+                .ok_or_else(|| Error::UnknownGlyph {
+                    index,
+                    glyph: glyph.replace('|', "\n"),
+                })
+        })
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{recognize, recognize_grid, Error, FONT_10, FONT_6};
+
+    /// Draws a word with one blank column between letters, as the puzzles do.
+    fn draw(font: &[(char, &str)], word: &str) -> String {
+        let glyphs: Vec<Vec<&str>> = word
+            .chars()
+            .map(|c| {
+                let (_, pattern) = font.iter().find(|(x, _)| *x == c).unwrap();
+                pattern.split('|').collect()
+            })
+            .collect();
+
+        (0..glyphs[0].len())
+            .map(|y| glyphs.iter().map(|g| g[y]).collect::<Vec<_>>().join("."))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn recognizes_every_small_letter() {
+        let word: String = FONT_6.iter().map(|(c, _)| *c).collect();
+        assert_eq!(recognize(&draw(FONT_6, &word)).unwrap(), word);
+    }
+
+    #[test]
+    fn recognizes_every_large_letter() {
+        let word: String = FONT_10.iter().map(|(c, _)| *c).collect();
+        assert_eq!(recognize(&draw(FONT_10, &word)).unwrap(), word);
+    }
+
+    #[test]
+    fn ignores_padding_and_pixel_characters() {
+        let art = draw(FONT_6, "EH")
+            .replace('#', "█")
+            .replace('.', " ")
+            .lines()
+            .map(|l| format!("  {l}  "))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(recognize(&format!("\n{art}\n\n")).unwrap(), "EH");
+    }
+
+    #[test]
+    fn recognizes_grids() {
+        let grid: Vec<Vec<bool>> = draw(FONT_6, "LO")
+            .lines()
+            .map(|l| l.chars().map(|c| c == '#').collect())
+            .collect();
+
+        assert_eq!(recognize_grid(&grid).unwrap(), "LO");
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(recognize("...\n..."), Err(Error::Empty));
+        assert_eq!(recognize("#\n#\n#"), Err(Error::UnsupportedHeight(3)));
+        assert_eq!(
+            recognize(&format!(
+                "{}\n",
+                draw(FONT_6, "A").replace(".##.\n", "####\n")
+            )),
+            Err(Error::UnknownGlyph {
+                index: 0,
+                glyph: "####\n#..#\n#..#\n####\n#..#\n#..#".into()
+            })
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Named constants of a solution that differ between runs, e.g. the expansion factor of a puzzle
+/// whose examples use other values than the real input.
+///
+/// Implemented by the `Params` struct that `solution!` generates from a `params { .. }` block,
+/// and by `()` for solutions without parameters.
+pub trait SolutionParams: Default {
+    /// Overrides the parameter `name`, parsing the value from its string representation.
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String>;
+
+    /// Starts from the declared defaults and applies all overrides in order.
+    fn from_overrides(overrides: &[Param]) -> Result<Self, String> {
+        let mut params = Self::default();
+        for param in overrides {
+            params.set(&param.name, &param.value)?;
+        }
+        Ok(params)
+    }
+}
+
+impl SolutionParams for () {
+    fn set(&mut self, name: &str, _value: &str) -> Result<(), String> {
+        Err(format!(
+            "unknown parameter `{name}`, this solution does not declare any parameters."
+        ))
+    }
+}
+
+/// A parameter override as passed on the command-line, e.g. `--param factor=10`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub value: String,
+}
+
+impl FromStr for Param {
+    type Err = ParamFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => Ok(Param {
+                name: name.trim().to_string(),
+                value: value.trim().to_string(),
+            }),
+            _ => Err(ParamFromStrError),
+        }
+    }
+}
+
+impl Display for Param {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.name, self.value)
+    }
+}
+
+/// An error which can be returned when parsing a [`Param`].
+#[derive(Debug)]
+pub struct ParamFromStrError;
+
+impl std::error::Error for ParamFromStrError {}
+
+impl Display for ParamFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a parameter in the form `name=value`")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Param, SolutionParams};
+    use std::str::FromStr;
+
+    #[derive(Debug, PartialEq)]
+    struct Limits {
+        red: u32,
+        blue: u32,
+    }
+
+    impl Default for Limits {
+        fn default() -> Self {
+            Self { red: 12, blue: 14 }
+        }
+    }
+
+    impl SolutionParams for Limits {
+        fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+            let value = value.parse().map_err(|_| format!("invalid `{name}`."))?;
+            match name {
+                "red" => self.red = value,
+                "blue" => self.blue = value,
+                _ => return Err(format!("unknown parameter `{name}`.")),
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn parses_params() {
+        let param = Param::from_str("factor = 10").unwrap();
+        assert_eq!(param.name, "factor");
+        assert_eq!(param.value, "10");
+        assert_eq!(param.to_string(), "factor=10");
+        assert!(Param::from_str("factor").is_err());
+        assert!(Param::from_str("=10").is_err());
+    }
+
+    #[test]
+    fn applies_overrides_to_defaults() {
+        let overrides = [Param::from_str("blue=20").unwrap()];
+        let limits = Limits::from_overrides(&overrides).unwrap();
+        assert_eq!(limits, Limits { red: 12, blue: 20 });
+    }
+
+    #[test]
+    fn rejects_unknown_params() {
+        let overrides = [Param::from_str("green=1").unwrap()];
+        assert!(Limits::from_overrides(&overrides).is_err());
+        assert!(<()>::from_overrides(&overrides).is_err());
+        assert!(<()>::from_overrides(&[]).is_ok());
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::HashSet, io};
+use std::{collections::HashSet, fmt::Display, io};
 
 use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
@@ -24,7 +24,8 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
             println!("------");
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+            let output = child_commands::run_solution(day, is_timed, is_release)
+                .unwrap_or_else(|e| panic!("failed to run solution for day {day}: {e}"));
 
             if output.is_empty() {
                 println!("Not solved.");
@@ -52,6 +53,15 @@ pub enum Error {
     IO(io::Error),
 }
 
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::BrokenPipe => write!(f, "could not capture output of child process."),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
         Error::IO(e)
@@ -67,7 +77,8 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
     use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use crate::template::runner::{parse_outcome_marker, REPORT_OUTCOMES_VAR};
+    use crate::template::{Day, Part};
     use std::{
         io::{BufRead, BufReader},
         path::Path,
@@ -100,6 +111,7 @@ pub mod child_commands {
 
         let mut cmd = Command::new("cargo")
             .args(&args)
+            .env(REPORT_OUTCOMES_VAR, "1")
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()?;
@@ -117,7 +129,9 @@ pub mod child_commands {
 
         for line in stdout.lines() {
             let line = line.unwrap();
-            println!("{line}");
+            if parse_outcome_marker(&line).is_none() {
+                println!("{line}");
+            }
             output.push(line);
         }
 
@@ -132,6 +146,8 @@ pub mod child_commands {
             day,
             part_1: None,
             part_2: None,
+            status_1: None,
+            status_2: None,
             total_nanos: 0_f64,
         };
 
@@ -160,6 +176,14 @@ pub mod child_commands {
                 timings.total_nanos += nanos;
             });
 
+        output
+            .iter()
+            .filter_map(|l| parse_outcome_marker(l))
+            .for_each(|(part, outcome)| match part {
+                Part::One => timings.status_1 = Some(outcome),
+                Part::Two => timings.status_2 = Some(outcome),
+            });
+
         timings
     }
 
@@ -172,8 +196,8 @@ pub mod child_commands {
         let str_timing = line
             .split(" samples)")
             .next()?
-            .split('(')
-            .last()?
+            .rsplit('(')
+            .next()?
             .split('@')
             .next()?
             .trim();
@@ -207,6 +231,8 @@ pub mod child_commands {
         use super::parse_exec_time;
 
         use crate::day;
+        use crate::template::runner::{outcome_marker, Outcome};
+        use crate::template::Part;
 
         #[test]
         fn parses_execution_times() {
@@ -252,5 +278,45 @@ pub mod child_commands {
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
         }
+
+        #[test]
+        fn parses_outcomes() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
+                    outcome_marker(Part::One, Outcome::Solved),
+                    "Part 2: ✖\rPart 2: ✖ panicked at src/bin/01.rs:3:5: oops".into(),
+                    outcome_marker(Part::Two, Outcome::Panic),
+                    "".into(),
+                ],
+                day!(1),
+            );
+            assert_eq!(res.status_1, Some(Outcome::Solved));
+            assert_eq!(res.status_2, Some(Outcome::Panic));
+
+            let res = parse_exec_time(
+                &[
+                    outcome_marker(Part::One, Outcome::NotImplemented),
+                    outcome_marker(Part::Two, Outcome::Error),
+                ],
+                day!(1),
+            );
+            assert_eq!(res.status_1, Some(Outcome::NotImplemented));
+            assert_eq!(res.status_2, Some(Outcome::Error));
+        }
+
+        #[test]
+        fn ignores_outcomes_in_answers() {
+            // answers and error messages may contain anything, e.g. the failure symbol.
+            let res = parse_exec_time(
+                &[
+                    "Part 1: ✖ error: no ✖ panicked".into(),
+                    "Part 2: a ::aoc-outcome:: 2 panic".into(),
+                ],
+                day!(1),
+            );
+            assert_eq!(res.status_1, None);
+            assert_eq!(res.status_2, None);
+        }
     }
 }
@@ -2,54 +2,393 @@
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
+use std::panic::{self, AssertUnwindSafe};
 use std::process::Output;
+use std::str::FromStr;
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::memo::{self, Stats};
+use crate::template::answers::Answers;
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{
+    aoc_cli, ocr, Config, Day, Param, Part, SolutionParams, ANSI_ITALIC, ANSI_RESET,
+};
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Arguments that `cargo solve` passes on to a solution binary.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunArguments {
+    /// Bench the solution instead of running it once.
+    pub time: bool,
+    /// Submit the answer of this part via aoc-cli.
+    pub submit: Option<Part>,
+    /// Overrides of the solution's declared parameters.
+    pub params: Vec<Param>,
+    /// Decode answers drawn in block letters, and record and submit the decoded letters.
+    pub ocr: bool,
+}
+
+impl RunArguments {
+    /// Parses the arguments of the current process, exiting if they are malformed.
+    pub fn from_env() -> Self {
+        let mut args = pico_args::Arguments::from_env();
+
+        let parsed = args.opt_value_from_str("--submit").and_then(|submit| {
+            Ok(Self {
+                time: args.contains("--time"),
+                submit,
+                params: args.values_from_str("--param")?,
+                ocr: args.contains("--ocr"),
+            })
+        });
+
+        match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                eprintln!("Usage: <solution> [--time] [--submit <part>] [--param <name=value>]... [--ocr]");
+                process::exit(1);
+            }
+        }
+    }
+
+    /// Applies the `--param` overrides to the solution's declared defaults, exiting if one is invalid.
+    pub fn solution_params<P: SolutionParams>(&self) -> P {
+        P::from_overrides(&self.params).unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        })
+    }
+
+    /// The command-line representation of these arguments, as read by [`RunArguments::from_env`].
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec![];
+
+        if let Some(part) = self.submit {
+            args.push("--submit".to_string());
+            args.push(part.to_string());
+        }
+
+        if self.time {
+            args.push("--time".to_string());
+        }
+
+        for param in &self.params {
+            args.push("--param".to_string());
+            args.push(param.to_string());
+        }
+
+        if self.ocr {
+            args.push("--ocr".to_string());
+        }
+
+        args
+    }
+}
+
+/// The result of running a solution part once.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PartResult<T> {
+    Solved(T),
+    /// The part returned `None` or hit `todo!()` / `unimplemented!()`.
+    NotImplemented,
+    /// The part returned an `Err`.
+    Error(String),
+    /// The part panicked. The location is the panic's source location, if known.
+    Panic {
+        message: String,
+        location: Option<String>,
+    },
+}
+
+impl<T> PartResult<T> {
+    pub fn outcome(&self) -> Outcome {
+        match self {
+            PartResult::Solved(_) => Outcome::Solved,
+            PartResult::NotImplemented => Outcome::NotImplemented,
+            PartResult::Error(_) => Outcome::Error,
+            PartResult::Panic { .. } => Outcome::Panic,
+        }
+    }
+
+    /// Describes why a part did not produce an answer, e.g. `error: invalid input`.
+    fn failure_description(&self) -> Option<String> {
+        match self {
+            PartResult::Solved(_) => None,
+            PartResult::NotImplemented => Some("not implemented".into()),
+            PartResult::Error(e) => Some(format!("error: {e}")),
+            PartResult::Panic {
+                message,
+                location: Some(location),
+            } => Some(format!("panicked at {location}: {message}")),
+            PartResult::Panic {
+                message,
+                location: None,
+            } => Some(format!("panicked: {message}")),
+        }
+    }
+}
+
+/// Return types that solution parts may use, i.e. `Option<T>` and `Result<T, E>`.
+pub trait IntoPartResult {
+    type Output: Display;
+
+    fn into_part_result(self) -> PartResult<Self::Output>;
+}
+
+impl<T: Display> IntoPartResult for Option<T> {
+    type Output = T;
+
+    fn into_part_result(self) -> PartResult<T> {
+        match self {
+            Some(x) => PartResult::Solved(x),
+            None => PartResult::NotImplemented,
+        }
+    }
+}
+
+impl<T: Display, E: Display> IntoPartResult for Result<T, E> {
+    type Output = T;
+
+    fn into_part_result(self) -> PartResult<T> {
+        match self {
+            Ok(x) => PartResult::Solved(x),
+            Err(e) => PartResult::Error(e.to_string()),
+        }
+    }
+}
+
+/// How running a solution part ended. Recorded in the timings file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Solved,
+    NotImplemented,
+    Error,
+    Panic,
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Solved => "solved",
+            Outcome::NotImplemented => "not_implemented",
+            Outcome::Error => "error",
+            Outcome::Panic => "panic",
+        }
+    }
+}
+
+/// Set by `cargo all` and `cargo time`, which read the outcome of every part from the output of
+/// the solution binaries.
+pub const REPORT_OUTCOMES_VAR: &str = "AOC_REPORT_OUTCOMES";
+
+const OUTCOME_MARKER: &str = "::aoc-outcome::";
+
+/// A line like `::aoc-outcome:: 1 error`, printed by [`run_part`] when [`REPORT_OUTCOMES_VAR`] is
+/// set.
+pub fn outcome_marker(part: Part, outcome: Outcome) -> String {
+    format!("{OUTCOME_MARKER} {part} {}", outcome.as_str())
+}
+
+/// Parses a line printed by [`outcome_marker`], `None` for any other line.
+pub fn parse_outcome_marker(line: &str) -> Option<(Part, Outcome)> {
+    let (part, outcome) = line.strip_prefix(OUTCOME_MARKER)?.trim().split_once(' ')?;
+    Some((part.parse().ok()?, outcome.parse().ok()?))
+}
+
+impl FromStr for Outcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "solved" => Ok(Outcome::Solved),
+            "not_implemented" => Ok(Outcome::NotImplemented),
+            "error" => Ok(Outcome::Error),
+            "panic" => Ok(Outcome::Panic),
+            _ => Err(format!("unknown outcome `{s}`.")),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Solution parts: either `fn(input)`, or `fn(input, &Params)` for solutions that declare parameters.
+/// The marker type only exists to tell the two implementations apart.
+pub trait PartFn<I, P, Marker> {
+    type Output: IntoPartResult;
+
+    fn call(&self, input: I, params: &P) -> Self::Output;
+}
+
+#[doc(hidden)]
+pub struct WithoutParams;
+
+#[doc(hidden)]
+pub struct WithParams;
+
+impl<I, P, R: IntoPartResult, F: Fn(I) -> R> PartFn<I, P, WithoutParams> for F {
+    type Output = R;
+
+    fn call(&self, input: I, _params: &P) -> R {
+        self(input)
+    }
+}
+
+impl<I, P, R: IntoPartResult, F: Fn(I, &P) -> R> PartFn<I, P, WithParams> for F {
+    type Output = R;
+
+    fn call(&self, input: I, params: &P) -> R {
+        self(input, params)
+    }
+}
+
+pub fn run_part<I: Clone, P, M>(
+    func: impl PartFn<I, P, M>,
+    input: I,
+    day: Day,
+    part: Part,
+    args: &RunArguments,
+    params: &P,
+) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    // overridden parameters may turn on diagnostics, e.g. a dump or a render to stderr, which
+    // would be repeated by every bench iteration and distort the timings.
+    let bench = args.time && args.params.is_empty();
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    let func = |input: I| func.call(input, params);
+    let (result, duration, samples, memo_stats) = run_timed(func, input, part, bench, |result| {
+        print_result(result, &part_str, "", None);
+    });
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    let decoded = match &result {
+        PartResult::Solved(result) if args.ocr => decode_block_letters(&result.to_string()),
+        _ => None,
+    };
+    let duration_str = format_duration(&duration, samples);
+    print_result(&result, &part_str, &duration_str, decoded.as_deref());
+
+    if args.time && !bench {
+        println!("{ANSI_ITALIC}  not benched, as parameters are overridden{ANSI_RESET}");
+    }
+
+    if env::var_os(REPORT_OUTCOMES_VAR).is_some() {
+        println!("{}", outcome_marker(part, result.outcome()));
+    }
+
+    if bench && memo_stats.lookups() > 0 {
+        println!("{ANSI_ITALIC}  memo: {memo_stats}{ANSI_RESET}");
+    }
+
+    if let PartResult::Solved(result) = result {
+        let answer = decoded.unwrap_or_else(|| result.to_string());
+
+        // answers computed with overridden parameters are not answers to the puzzle input.
+        if args.params.is_empty() {
+            record_answer(&answer, day, part);
+        }
+        if args.submit == Some(part) {
+            submit_result(answer, day, part);
+        }
+    }
+}
+
+/// Decodes multi-line answers that spell out block letters, e.g. a drawn `EFEYKFRP`.
+fn decode_block_letters(answer: &str) -> Option<String> {
+    if answer.contains('\n') {
+        ocr::recognize(answer).ok()
+    } else {
+        None
+    }
+}
+
+/// Store the answer in the answers file, so that `cargo status` can report it.
+/// The file is left untouched if the answer did not change.
+fn record_answer<T: Display>(result: &T, day: Day, part: Part) {
+    let mut answers = Answers::read_from_file();
+    if !answers.record(day, part, result.to_string()) {
+        return;
+    }
+
+    if let Err(e) = answers.store_file() {
+        eprintln!("Failed to record answer: {e}");
     }
 }
 
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Clone, T>(
-    func: impl Fn(I) -> T,
+fn run_timed<I: Clone, R: IntoPartResult>(
+    func: impl Fn(I) -> R,
     input: I,
-    hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+    #[cfg_attr(not(feature = "dhat-heap"), allow(unused_variables))] part: Part,
+    time: bool,
+    hook: impl Fn(&PartResult<R::Output>),
+) -> (PartResult<R::Output>, Duration, u128, Stats) {
+    memo::take_global_stats();
     let timer = Instant::now();
-    let result = {
-        let input = input.clone();
-
-        #[cfg(feature = "dhat-heap")]
-        let _profiler = dhat::Profiler::new_heap();
-
-        func(input)
-    };
+    let result = catch_panic(|| {
+        let output = {
+            let input = input.clone();
+            // each part writes its own profile, which leaves out reading the input and
+            // recording the answer.
+            #[cfg(feature = "dhat-heap")]
+            let _profiler = dhat::Profiler::builder()
+                .file_name(crate::template::dhat_report::profile_path(part))
+                .build();
+            func(input)
+        };
+        output.into_part_result()
+    });
     let base_time = timer.elapsed();
+    // only the first run counts, benching would repeat the same lookups.
+    let memo_stats = memo::take_global_stats();
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
+    // failed parts are not benched.
+    let run = if time && result.outcome() == Outcome::Solved {
         bench(func, input, &base_time)
     } else {
         (base_time, 1)
     };
 
-    (result, run.0, run.1)
+    (result, run.0, run.1, memo_stats)
+}
+
+/// Runs a part, converting a panic into a [`PartResult`] instead of aborting the remaining parts.
+fn catch_panic<T>(func: impl FnOnce() -> PartResult<T>) -> PartResult<T> {
+    static LOCATION: Mutex<Option<String>> = Mutex::new(None);
+
+    // replace the default hook, which would print the panic to stderr, while the part runs.
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|info| {
+        if let Ok(mut location) = LOCATION.lock() {
+            *location = info.location().map(ToString::to_string);
+        }
+    }));
+
+    let result = panic::catch_unwind(AssertUnwindSafe(func));
+    panic::set_hook(default_hook);
+
+    result.unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(ToString::to_string)
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+
+        // `todo!()` and `unimplemented!()`
+        if message.starts_with("not implemented") || message.starts_with("not yet implemented") {
+            return PartResult::NotImplemented;
+        }
+
+        PartResult::Panic {
+            message,
+            location: LOCATION.lock().ok().and_then(|mut l| l.take()),
+        }
+    })
 }
 
 fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
@@ -58,13 +397,10 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
     let _ = stdout.flush();
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    let config = &Config::get().benchmark;
+    let bench_iterations = (Duration::from_millis(config.target_millis).as_nanos()
+        / cmp::max(base_time.as_nanos(), 10))
+    .clamp(config.min_samples, config.max_samples);
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -99,11 +435,17 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+/// `decoded` holds the letters a drawn answer spells out, if they were decoded.
+fn print_result<T: Display>(
+    result: &PartResult<T>,
+    part: &str,
+    duration_str: &str,
+    decoded: Option<&str>,
+) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
-        Some(result) => {
+        PartResult::Solved(result) => {
             if result.to_string().contains('\n') {
                 let str = format!("{part}: ▼ {duration_str}");
                 if is_intermediate_result {
@@ -112,6 +454,9 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                     print!("\r");
                     println!("{str}");
                     println!("{result}");
+                    if let Some(decoded) = decoded {
+                        println!("{part}: {ANSI_BOLD}{decoded}{ANSI_RESET} (decoded)");
+                    }
                 }
             } else {
                 let str = format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{duration_str}");
@@ -123,47 +468,24 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 }
             }
         }
-        None => {
+        failure => {
             if is_intermediate_result {
                 print!("{part}: ✖");
             } else {
+                let description = failure.failure_description().unwrap_or_default();
                 print!("\r");
-                println!("{part}: ✖             ");
+                println!("{part}: ✖ {description}");
             }
         }
     }
 }
 
-/// Parse the arguments passed to `solve` and try to submit one part of the solution if:
-///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
+/// Try to submit one part of the solution if aoc-cli is installed.
 fn submit_result<T: Display>(
     result: T,
     day: Day,
-    part: u8,
+    part: Part,
 ) -> Option<Result<Output, aoc_cli::AocCommandError>> {
-    let args: Vec<String> = env::args().collect();
-
-    if !args.contains(&"--submit".into()) {
-        return None;
-    }
-
-    if args.len() < 3 {
-        eprintln!("Unexpected command-line input. Format: cargo solve 1 --submit 1");
-        process::exit(1);
-    }
-
-    let part_index = args.iter().position(|x| x == "--submit").unwrap() + 1;
-
-    let Ok(part_submit) = args[part_index].parse::<u8>() else {
-        eprintln!("Unexpected command-line input. Format: cargo solve 1 --submit 1");
-        process::exit(1);
-    };
-
-    if part_submit != part {
-        return None;
-    }
-
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
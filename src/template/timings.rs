@@ -1,9 +1,8 @@
 use std::{collections::HashMap, fs, io::Error, str::FromStr};
 use tinyjson::JsonValue;
 
-use crate::template::Day;
-
-static TIMINGS_FILE_PATH: &str = "./data/timings.json";
+use crate::template::runner::Outcome;
+use crate::template::{Config, Day};
 
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
@@ -11,6 +10,9 @@ pub struct Timing {
     pub day: Day,
     pub part_1: Option<String>,
     pub part_2: Option<String>,
+    /// How the last run of each part ended. Not present in timings written by older versions.
+    pub status_1: Option<Outcome>,
+    pub status_2: Option<Outcome>,
     pub total_nanos: f64,
 }
 
@@ -25,13 +27,18 @@ impl Timings {
     /// Dehydrate timings to a JSON file.
     pub fn store_file(&self) -> Result<(), Error> {
         let json = JsonValue::from(self.clone());
-        let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
+        let mut file = fs::File::create(Config::get().timings_file())?;
         json.format_to(&mut file)
     }
 
     /// Rehydrate timings from a JSON file. If not present, returns empty timings.
     pub fn read_from_file() -> Self {
-        let s = fs::read_to_string(TIMINGS_FILE_PATH)
+        let path = &Config::get().timings_file();
+        if !path.exists() {
+            return Timings::default();
+        }
+
+        let s = fs::read_to_string(path)
             .map_err(|x| x.to_string())
             .and_then(Timings::try_from);
 
@@ -58,7 +65,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|a| a.day);
         Timings { data }
     }
 
@@ -140,6 +147,16 @@ impl From<&Timing> for JsonValue {
             },
         );
 
+        for (key, status) in [("status_1", value.status_1), ("status_2", value.status_2)] {
+            map.insert(
+                key.into(),
+                match status {
+                    Some(x) => JsonValue::String(x.as_str().into()),
+                    None => JsonValue::Null,
+                },
+            );
+        }
+
         JsonValue::Object(map)
     }
 }
@@ -168,6 +185,19 @@ impl TryFrom<&JsonValue> for Timing {
             .map(|v| if v.is_null() { None } else { v.get::<String>() })
             .ok_or("Expected timing.part_2 to be null or string.")?;
 
+        let status = |key: &str| match json.get(key) {
+            None => Ok(None),
+            Some(v) if v.is_null() => Ok(None),
+            Some(v) => v
+                .get::<String>()
+                .and_then(|s| Outcome::from_str(s).ok())
+                .map(Some)
+                .ok_or(format!("Expected timing.{key} to be null or an outcome.")),
+        };
+
+        let status_1 = status("status_1")?;
+        let status_2 = status("status_2")?;
+
         let total_nanos = json
             .get("total_nanos")
             .and_then(|v| v.get::<f64>().copied())
@@ -177,6 +207,8 @@ impl TryFrom<&JsonValue> for Timing {
             day,
             part_1: part_1.cloned(),
             part_2: part_2.cloned(),
+            status_1,
+            status_2,
             total_nanos,
         })
     }
@@ -197,18 +229,24 @@ mod tests {
                     day: day!(1),
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
+                    status_1: None,
+                    status_2: None,
                     total_nanos: 3e+10,
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
+                    status_1: None,
+                    status_2: None,
                     total_nanos: 7e+10,
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: None,
+                    status_1: None,
+                    status_2: None,
                     total_nanos: 4e+10,
                 },
             ],
@@ -216,7 +254,7 @@ mod tests {
     }
 
     mod deserialization {
-        use crate::{day, template::timings::Timings};
+        use crate::{day, template::runner::Outcome, template::timings::Timings};
 
         #[test]
         fn handles_json_timings() {
@@ -227,9 +265,19 @@ mod tests {
             assert_eq!(timing.day, day!(1));
             assert_eq!(timing.part_1, Some("1ms".to_string()));
             assert_eq!(timing.part_2, None);
+            assert_eq!(timing.status_1, None);
             assert_eq!(timing.total_nanos, 1_000_000_000_f64);
         }
 
+        #[test]
+        fn handles_json_outcomes() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "status_1": "solved", "status_2": "panic", "total_nanos": 1000000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.status_1, Some(Outcome::Solved));
+            assert_eq!(timing.status_2, Some(Outcome::Panic));
+        }
+
         #[test]
         fn handles_empty_timings() {
             let json = r#"{ "data": [] }"#.to_string();
@@ -288,11 +336,13 @@ mod tests {
                     day: day!(1),
                     part_1: Some("1ms".into()),
                     part_2: Some("2ms".into()),
+                    status_1: None,
+                    status_2: None,
                     total_nanos: 3_000_000_000_f64,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -302,11 +352,13 @@ mod tests {
                     day: day!(1),
                     part_1: Some("1ms".into()),
                     part_2: None,
+                    status_1: None,
+                    status_2: None,
                     total_nanos: 1_000_000_000_f64,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
 
         #[test]
@@ -316,11 +368,13 @@ mod tests {
                     day: day!(1),
                     part_1: None,
                     part_2: None,
+                    status_1: None,
+                    status_2: None,
                     total_nanos: 0.0,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 
@@ -340,6 +394,8 @@ mod tests {
                     day: day!(3),
                     part_1: None,
                     part_2: None,
+                    status_1: None,
+                    status_2: None,
                     total_nanos: 0_f64,
                 }],
             };
@@ -360,6 +416,8 @@ mod tests {
                     day: day!(2),
                     part_1: None,
                     part_2: None,
+                    status_1: None,
+                    status_2: None,
                     total_nanos: 0_f64,
                 }],
             };